@@ -22,7 +22,7 @@
 //!     message: String::from("Derive works"),
 //! })?;
 //! # out.flush()?;
-//! # assert_eq!(test_target.to_string(), "code: 42\nmessage: Derive works\n\n");
+//! # assert_eq!(test_target.to_string(), "code:    42\nmessage: Derive works\n");
 //! # Ok(()) }
 //! ```
 
@@ -54,21 +54,19 @@ pub fn render_output(input: TokenStream) -> TokenStream {
                     .iter()
                     .map(|f| f.ident.clone().unwrap().to_string());
                 quote! {
-                    let mut span = convey::components::span();
+                    let mut kv = convey::components::kv();
                     #(
-                        span = span.add_item(#names);
-                        span = span.add_item(": ");
-                        span = span.add_item(convey::components::text(&self.#fields.to_string()));
-                        span = span.add_item("\n");
+                        kv = kv.entry(#names, convey::components::text(&self.#fields.to_string()));
                     )*
-                    span.render_for_humans(fmt)?;
+                    kv.render_for_humans(fmt)?;
                 }
             }
             Fields::Unnamed(..) => {
                 let field_count = s.fields.iter().count();
                 let fields = (0..field_count)
                         .fold(Vec::new(), |mut res, i| {
-                            res.push(quote! { span = span.add_item(convey::components::text(&self.#i.to_string())); });
+                            let index = syn::Index::from(i);
+                            res.push(quote! { span = span.add_item(convey::components::text(&self.#index.to_string())); });
                             if i < field_count - 1 {
                                 res.push(quote! { span = span.add_item(", "); });
                             }
@@ -28,9 +28,9 @@ fn struct_with_named_fields_of_primitive_types() -> Result<(), convey::Error> {
 
     assert_eq!(
         human.to_string(),
-        "code: 42\n\
-         name: info\n\
-         message: Derive works\n\n"
+        "code:    42\n\
+         name:    info\n\
+         message: Derive works\n"
     );
 
     assert_eq!(
@@ -42,6 +42,8 @@ fn struct_with_named_fields_of_primitive_types() -> Result<(), convey::Error> {
 }
 
 #[test]
+// Printing a reference is part of what this covers
+#[allow(clippy::needless_borrows_for_generic_args)]
 fn tuple_struct_of_primitive_types() -> Result<(), convey::Error> {
     #[derive(Serialize, RenderOutput)]
     struct ErrorMessage(i32, String);
@@ -1,5 +1,3 @@
-use failure;
-use structopt;
 #[macro_use]
 extern crate serde_derive;
 #[macro_use]
@@ -38,6 +36,16 @@ fn main() -> Result<(), failure::Error> {
         span!(underline = true, ["underlined text",]),
         newline(),
         span!(intense = true, ["intense text",]),
+        newline(),
+        span!(dim = true, ["dim text",]),
+        newline(),
+        span!(italic = true, ["italic text",]),
+        newline(),
+        span!(strikethrough = true, ["strikethrough text",]),
+        newline(),
+        span!(reverse = true, ["reversed text",]),
+        newline(),
+        span!(fg = "#ff8800", ["hex colored text",]),
     ]))?;
 
     #[derive(Serialize)]
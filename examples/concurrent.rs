@@ -1,6 +1,3 @@
-use convey;
-use failure;
-
 use convey::{human, json};
 use rand::{thread_rng, Rng};
 use std::thread;
@@ -17,7 +14,7 @@ fn main() -> Result<(), failure::Error> {
         let t = thread::spawn(move || {
             let dur = Duration::from_millis(thread_rng().gen_range(0u64, 1));
             thread::sleep(dur);
-            out.print(&format!("thread {} says hello", i))
+            out.print(format!("thread {} says hello", i))
         });
         threads.push(t);
     }
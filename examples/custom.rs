@@ -1,4 +1,3 @@
-use failure;
 #[macro_use]
 extern crate convey;
 #[macro_use]
@@ -1,5 +1,3 @@
-use convey;
-use failure;
 #[macro_use]
 extern crate convey_derive;
 #[macro_use]
@@ -0,0 +1,325 @@
+//! Colors and terminal color capabilities
+//!
+//! Colors can be given by name (`"red"`), as an index into the 256 color
+//! palette (`"208"`), as RGB triples (`"255,136,0"`), or in hex notation
+//! (`"#ff8800"`, `"#f80"`).
+//!
+//! Not every terminal can display all of these. Human targets detect what the
+//! terminal supports (see [`ColorSupport::detect`]) and map each color to the
+//! nearest one that it can display.
+
+use crate::Error;
+use std::env;
+use termcolor::ColorSpec;
+
+pub use termcolor::Color;
+
+/// Types that can be used as colors
+///
+/// This is implemented for strings (parsed as described in the [module
+/// level documentation](index.html)), [`Color`]s, and RGB tuples.
+pub trait ToColor {
+    /// Try to convert to a color
+    fn to_color(&self) -> Result<Color, Error>;
+}
+
+impl ToColor for str {
+    fn to_color(&self) -> Result<Color, Error> {
+        parse_color(self)
+    }
+}
+
+impl ToColor for String {
+    fn to_color(&self) -> Result<Color, Error> {
+        parse_color(self)
+    }
+}
+
+impl ToColor for Color {
+    fn to_color(&self) -> Result<Color, Error> {
+        Ok(*self)
+    }
+}
+
+impl ToColor for (u8, u8, u8) {
+    fn to_color(&self) -> Result<Color, Error> {
+        Ok(Color::Rgb(self.0, self.1, self.2))
+    }
+}
+
+impl<T: ToColor + ?Sized> ToColor for &T {
+    fn to_color(&self) -> Result<Color, Error> {
+        (*self).to_color()
+    }
+}
+
+/// Parse a color, additionally supporting hex notation
+pub(crate) fn parse_color(input: &str) -> Result<Color, Error> {
+    if !input.starts_with('#') {
+        // Numbers are parsed with `u8::from_str_radix`, which also accepts a
+        // leading `+`
+        let numeric =
+            input.contains(',') || input.starts_with(|c: char| c.is_ascii_digit() || c == '+');
+        if numeric && !input.split(',').all(is_number) {
+            return Err(Error::invalid_color(input));
+        }
+        return Ok(input.parse()?);
+    }
+
+    let hex = &input[1..];
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::invalid_color(input));
+    }
+    let channel = |range: std::ops::Range<usize>| {
+        hex.get(range)
+            .and_then(|x| u8::from_str_radix(x, 16).ok())
+            .ok_or_else(|| Error::invalid_color(input))
+    };
+
+    match hex.len() {
+        3 => Ok(Color::Rgb(
+            channel(0..1)? * 0x11,
+            channel(1..2)? * 0x11,
+            channel(2..3)? * 0x11,
+        )),
+        6 => Ok(Color::Rgb(channel(0..2)?, channel(2..4)?, channel(4..6)?)),
+        _ => Err(Error::invalid_color(input)),
+    }
+}
+
+/// Whether `text` is a decimal number, or a hex number starting with `0x`
+fn is_number(text: &str) -> bool {
+    match text.strip_prefix("0x") {
+        Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => !text.is_empty() && text.chars().all(|c| c.is_ascii_digit()),
+    }
+}
+
+/// Colors a terminal is able to display
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// The 8 basic colors and their intense variants
+    Ansi16,
+    /// The 256 color palette
+    Ansi256,
+    /// Arbitrary 24 bit RGB colors
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Detect the color support of the current terminal
+    ///
+    /// This looks at the `COLORTERM` and `TERM` environment variables and
+    /// falls back to 16 colors.
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+
+        if colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("direct") {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    /// Map a color to the nearest one that is supported
+    ///
+    /// Returns whether the color should be displayed in its intense variant,
+    /// which is how the bright colors of the 16 color palette are selected.
+    pub fn downgrade(self, color: Color) -> (Color, bool) {
+        match (self, color) {
+            (ColorSupport::TrueColor, color) => (color, false),
+            (ColorSupport::Ansi256, Color::Rgb(r, g, b)) => {
+                (Color::Ansi256(nearest_ansi256(r, g, b)), false)
+            }
+            (ColorSupport::Ansi256, color) => (color, false),
+            (ColorSupport::Ansi16, Color::Rgb(r, g, b)) => ansi16(nearest_ansi16(r, g, b)),
+            (ColorSupport::Ansi16, Color::Ansi256(n)) if n < 16 => ansi16(n),
+            (ColorSupport::Ansi16, Color::Ansi256(n)) => {
+                let (r, g, b) = ansi256_to_rgb(n);
+                ansi16(nearest_ansi16(r, g, b))
+            }
+            (ColorSupport::Ansi16, color) => (color, false),
+        }
+    }
+
+    /// Map all colors of a color spec to supported ones
+    pub(crate) fn downgrade_spec(self, spec: &ColorSpec) -> ColorSpec {
+        let mut spec = spec.clone();
+        let fg = spec.fg().map(|&c| self.downgrade(c));
+        let bg = spec.bg().map(|&c| self.downgrade(c));
+
+        // Intense applies to both colors, so only the foreground gets to
+        // decide whether it's used.
+        if let Some((_, true)) = fg {
+            spec.set_intense(true);
+        }
+        spec.set_fg(fg.map(|(c, _)| c));
+        spec.set_bg(bg.map(|(c, _)| c));
+        spec
+    }
+}
+
+/// RGB values of a color, e.g. to use it outside of terminals
+///
+/// Named colors use their intense variant if `intense` is set.
+pub(crate) fn to_rgb(color: Color, intense: bool) -> (u8, u8, u8) {
+    let bright = if intense { 8 } else { 0 };
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Ansi256(n) => ansi256_to_rgb(n),
+        Color::Black => ANSI16_RGB[bright],
+        Color::Red => ANSI16_RGB[1 + bright],
+        Color::Green => ANSI16_RGB[2 + bright],
+        Color::Yellow => ANSI16_RGB[3 + bright],
+        Color::Blue => ANSI16_RGB[4 + bright],
+        Color::Magenta => ANSI16_RGB[5 + bright],
+        Color::Cyan => ANSI16_RGB[6 + bright],
+        _ => ANSI16_RGB[7 + bright],
+    }
+}
+
+/// RGB values of the 16 color palette, as used by xterm
+const ANSI16_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Channel values of the 6x6x6 color cube of the 256 color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn ansi16(n: u8) -> (Color, bool) {
+    let color = match n % 8 {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        _ => Color::White,
+    };
+    (color, n >= 8)
+}
+
+fn ansi256_to_rgb(n: u8) -> (u8, u8, u8) {
+    match n {
+        0..=15 => ANSI16_RGB[n as usize],
+        16..=231 => {
+            let n = n - 16;
+            (
+                CUBE_LEVELS[(n / 36) as usize],
+                CUBE_LEVELS[(n / 6 % 6) as usize],
+                CUBE_LEVELS[(n % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (n - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> u8 {
+    (0..16u8)
+        .min_by_key(|&n| distance(ANSI16_RGB[n as usize], (r, g, b)))
+        .unwrap_or(0)
+}
+
+fn nearest_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |x: u8| {
+        (0..6u8)
+            .min_by_key(|&i| (i32::from(CUBE_LEVELS[i as usize]) - i32::from(x)).abs())
+            .unwrap_or(0)
+    };
+    let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+
+    let average = (u32::from(r) + u32::from(g) + u32::from(b)) / 3;
+    let gray = 232 + ((average.saturating_sub(8) + 5) / 10).min(23) as u8;
+
+    if distance(ansi256_to_rgb(gray), (r, g, b)) < distance(ansi256_to_rgb(cube), (r, g, b)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_color, Color, ColorSupport};
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!(parse_color("#ff8800").unwrap(), Color::Rgb(255, 136, 0));
+        assert_eq!(parse_color("#F80").unwrap(), Color::Rgb(255, 136, 0));
+        assert_eq!(parse_color("red").unwrap(), Color::Red);
+        assert_eq!(parse_color("208").unwrap(), Color::Ansi256(208));
+
+        assert!(parse_color("#ff88").is_err());
+        assert!(parse_color("#gg8800").is_err());
+        assert!(parse_color("#+f+f+f").is_err());
+        assert!(parse_color("+208").is_err());
+        assert!(parse_color("0,+1,2").is_err());
+        assert!(parse_color("0x+f").is_err());
+        assert_eq!(parse_color("0,0x10,2").unwrap(), Color::Rgb(0, 16, 2));
+    }
+
+    #[test]
+    fn keeps_colors_with_truecolor() {
+        let color = Color::Rgb(255, 136, 0);
+        assert_eq!(ColorSupport::TrueColor.downgrade(color), (color, false));
+    }
+
+    #[test]
+    fn maps_rgb_to_256_colors() {
+        let support = ColorSupport::Ansi256;
+        assert_eq!(
+            support.downgrade(Color::Rgb(255, 136, 0)).0,
+            Color::Ansi256(208)
+        );
+        assert_eq!(support.downgrade(Color::Rgb(0, 0, 0)).0, Color::Ansi256(16));
+        assert_eq!(
+            support.downgrade(Color::Rgb(128, 128, 128)).0,
+            Color::Ansi256(244)
+        );
+        assert_eq!(support.downgrade(Color::Ansi256(42)).0, Color::Ansi256(42));
+        assert_eq!(support.downgrade(Color::Red).0, Color::Red);
+    }
+
+    #[test]
+    fn maps_to_16_colors() {
+        let support = ColorSupport::Ansi16;
+        assert_eq!(
+            support.downgrade(Color::Rgb(200, 10, 10)),
+            (Color::Red, false)
+        );
+        assert_eq!(
+            support.downgrade(Color::Rgb(255, 255, 255)),
+            (Color::White, true)
+        );
+        assert_eq!(support.downgrade(Color::Ansi256(9)), (Color::Red, true));
+        assert_eq!(support.downgrade(Color::Ansi256(196)), (Color::Red, true));
+        assert_eq!(support.downgrade(Color::Blue), (Color::Blue, false));
+    }
+}
@@ -0,0 +1,116 @@
+use super::text::{text, Text};
+use crate::{html, human, json, markdown, Error, Render};
+use serde::Serialize;
+use std::fmt::{Debug, Display};
+
+/// Render a value using its `Display` implementation
+///
+/// In JSON, this is a string.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::display};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// let address: std::net::Ipv4Addr = "127.0.0.1".parse().unwrap();
+/// out.print(display(&address))?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "127.0.0.1\n");
+/// # Ok(()) }
+/// ```
+pub fn display<T: Display + ?Sized>(item: &T) -> Text {
+    text(item.to_string())
+}
+
+/// Render a value using its `Debug` implementation
+///
+/// In JSON, this is a string.
+pub fn debug<T: Debug + ?Sized>(item: &T) -> Text {
+    text(format!("{:?}", item))
+}
+
+/// Render something completely different for humans and machines
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, json, components::{human_json, text}};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let human_target = human::test();
+/// # let json_target = json::test();
+/// let mut out = convey::new()
+///     .add_target(human_target.target())?
+///     .add_target(json_target.target())?;
+/// out.print(human_json(
+///     text("Everything is fine."),
+///     serde_json::json!({ "success": true }),
+/// ))?;
+/// # out.flush()?;
+/// # assert_eq!(human_target.to_string(), "Everything is fine.\n");
+/// # assert_eq!(json_target.to_string(), "{\"success\":true}\n");
+/// # Ok(()) }
+/// ```
+pub fn human_json<H: Render, J: Serialize>(human: H, json: J) -> HumanJson<H, J> {
+    HumanJson { human, json }
+}
+
+pub struct HumanJson<H, J> {
+    human: H,
+    json: J,
+}
+
+impl<H: Render, J: Serialize> Render for HumanJson<H, J> {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        self.human.render_for_humans(fmt)
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(&self.json)?;
+        Ok(())
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        self.human.render_html(fmt)
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        self.human.render_markdown(fmt)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{debug, display, human_json};
+    use crate::{human, json, Error, Render};
+
+    fn render<T: Render>(item: T) -> Result<(String, String), Error> {
+        let human_output = human::test();
+        item.render_for_humans(&mut human_output.formatter())?;
+        let json_output = json::test();
+        item.render_json(&mut json_output.formatter())?;
+        Ok((human_output.to_string(), json_output.to_string()))
+    }
+
+    #[test]
+    fn renders_display_and_debug() -> Result<(), Error> {
+        assert_eq!(render(display(&4.5))?, ("4.5".into(), "\"4.5\"".into()));
+        assert_eq!(
+            render(debug(&Some("x")))?,
+            ("Some(\"x\")".into(), "\"Some(\\\"x\\\")\"".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_human_and_json_separately() -> Result<(), Error> {
+        assert_eq!(
+            render(human_json("2 files", vec!["a", "b"]))?,
+            ("2 files".into(), "[\"a\",\"b\"]".into())
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+use crate::color::Color;
+use crate::{html, human, json, markdown, Error, Render};
+use serde_json::json;
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{FontStyle, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+use termcolor::ColorSpec;
+
+/// Render source code with syntax highlighting
+///
+/// `language` is a name or file extension like `"rust"` or `"rs"`. Unknown
+/// languages are rendered as plain text. The highlighting theme comes from the
+/// target's theme (see [`Theme::with_syntax_theme`]), so code isn't
+/// highlighted with the monochrome theme. In JSON, this is an object like
+/// `{"language": "rust", "code": "..."}`.
+///
+/// This is only available with the `syntax-highlighting` feature.
+///
+/// [`Theme::with_syntax_theme`]: ../theme/struct.Theme.html#method.with_syntax_theme
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::code};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(
+///     code("fn main() {\n    run();\n}\n", "rust")
+///         .line_numbers(true)
+///         .highlight_lines(2..=2),
+/// )?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "  1 │ fn main() {\n> 2 │     run();\n  3 │ }\n");
+/// # Ok(()) }
+/// ```
+pub fn code<S: AsRef<str>, L: AsRef<str>>(source: S, language: L) -> Code {
+    Code {
+        source: source.as_ref().to_string(),
+        language: language.as_ref().to_string(),
+        line_numbers: false,
+        first_line: 1,
+        highlighted: Vec::new(),
+        theme: None,
+    }
+}
+
+pub struct Code {
+    source: String,
+    language: String,
+    line_numbers: bool,
+    first_line: usize,
+    highlighted: Vec<RangeInclusive<usize>>,
+    theme: Option<String>,
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    THEMES.get_or_init(ThemeSet::load_defaults)
+}
+
+impl Code {
+    /// Show line numbers in a gutter
+    pub fn line_numbers(mut self, yes: bool) -> Self {
+        self.line_numbers = yes;
+        self
+    }
+
+    /// Set the number of the first line (default: 1)
+    ///
+    /// Use this when showing an excerpt of a file.
+    pub fn first_line(mut self, line: usize) -> Self {
+        self.first_line = line;
+        self
+    }
+
+    /// Mark a range of lines, e.g. the ones an error refers to
+    ///
+    /// Line numbers are relative to [`first_line`](#method.first_line). This
+    /// can be called multiple times.
+    pub fn highlight_lines(mut self, lines: RangeInclusive<usize>) -> Self {
+        self.highlighted.push(lines);
+        self
+    }
+
+    /// Use a different highlighting theme than the target's theme does
+    ///
+    /// Available are the themes that come with `syntect`, e.g.
+    /// `InspiredGitHub` or `Solarized (light)`.
+    pub fn theme<T: AsRef<str>>(mut self, name: T) -> Self {
+        self.theme = Some(name.as_ref().to_string());
+        self
+    }
+
+    fn is_highlighted(&self, line: usize) -> bool {
+        self.highlighted.iter().any(|range| range.contains(&line))
+    }
+}
+
+fn color_spec(style: syntect::highlighting::Style) -> ColorSpec {
+    let color = style.foreground;
+    let mut spec = ColorSpec::new();
+    spec.set_fg(Some(Color::Rgb(color.r, color.g, color.b)))
+        .set_bold(style.font_style.contains(FontStyle::BOLD))
+        .set_italic(style.font_style.contains(FontStyle::ITALIC))
+        .set_underline(style.font_style.contains(FontStyle::UNDERLINE));
+    spec
+}
+
+impl Render for Code {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let syntaxes = syntax_set();
+        let syntax = syntaxes
+            .find_syntax_by_token(&self.language)
+            .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+        let mut highlighter = match self.theme.as_deref().or(fmt.theme().syntax_theme()) {
+            Some(name) => {
+                let theme = theme_set().themes.get(name).ok_or_else(|| {
+                    Error::theme_error(format!("unknown syntax highlighting theme `{}`", name))
+                })?;
+                Some(HighlightLines::new(syntax, theme))
+            }
+            None => None,
+        };
+
+        let line_count = self.source.lines().count();
+        let number_width = (self.first_line + line_count)
+            .saturating_sub(1)
+            .to_string()
+            .len();
+
+        for (i, line) in LinesWithEndings::from(&self.source).enumerate() {
+            if i > 0 {
+                fmt.write("\n")?;
+            }
+
+            let number = self.first_line + i;
+            let highlighted = self.is_highlighted(number);
+            if !self.highlighted.is_empty() {
+                fmt.write_styled("emphasis", if highlighted { "> " } else { "  " })?;
+            }
+            if self.line_numbers {
+                let vertical = fmt.glyphs().vertical;
+                let gutter = format!("{:>width$} {} ", number, vertical, width = number_width);
+                fmt.write_styled(if highlighted { "emphasis" } else { "gutter" }, gutter)?;
+            }
+
+            let highlighter = match &mut highlighter {
+                Some(highlighter) => highlighter,
+                None => {
+                    fmt.write(line.trim_end_matches(['\n', '\r']))?;
+                    continue;
+                }
+            };
+            let regions = highlighter
+                .highlight_line(line, syntaxes)
+                .map_err(|e| Error::theme_error(e.to_string()))?;
+            for (style, text) in regions {
+                let text = text.trim_end_matches(['\n', '\r']);
+                if text.is_empty() {
+                    continue;
+                }
+                fmt.set_color(&color_spec(style))?;
+                fmt.write(text)?;
+                fmt.reset()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(&json!({
+            "language": self.language,
+            "code": self.source,
+        }))
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        fmt.write_raw(format!(
+            "<pre><code class=\"language-{}\">{}</code></pre>",
+            html::escape(&self.language),
+            html::escape(&self.source)
+        ))
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        if fmt.inline() {
+            return fmt.write_human(self);
+        }
+        fmt.write_raw(markdown::code_block(&self.language, &self.source))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::code;
+    use crate::theme::Theme;
+    use crate::{human, json, Error, Render};
+
+    #[test]
+    fn highlights_syntax() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let mut fmt = test_target.formatter();
+        code("let x = 1;", "rs").render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        let output = test_target.to_string();
+        assert!(output.contains("\u{1b}[38;2;180;142;173mlet"));
+        assert!(output.contains("\u{1b}[38;2;208;135;112m1"));
+        Ok(())
+    }
+
+    #[test]
+    fn follows_the_targets_theme() -> Result<(), Error> {
+        let render = |theme: Theme| -> Result<String, Error> {
+            let test_target = human::test_with_color();
+            let out = crate::new().add_target(test_target.target().with_theme(theme)?)?;
+            out.print(code("let x = 1;", "rs"))?;
+            out.flush()?;
+            Ok(test_target.to_string())
+        };
+
+        assert!(render(Theme::light())?.contains("\u{1b}[38;2;167;29;93mlet"));
+        assert_eq!(render(Theme::monochrome())?, "let x = 1;\n");
+        Ok(())
+    }
+
+    #[test]
+    fn numbers_lines_of_excerpts() -> Result<(), Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        code("a\nb\nc", "unknown")
+            .first_line(9)
+            .line_numbers(true)
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(human_output.to_string(), " 9 │ a\n10 │ b\n11 │ c");
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unknown_themes() {
+        let fmt = human::test().formatter();
+        let result = code("x", "rs")
+            .theme("nope")
+            .render_for_humans(&mut fmt.clone());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn renders_json() -> Result<(), Error> {
+        let json = json::test();
+        let mut fmt = json.formatter();
+        code("x = 1", "python").render_json(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            json.to_string(),
+            "{\"code\":\"x = 1\",\"language\":\"python\"}"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+use crate::layout::Captured;
+use crate::{html, human, json, markdown, Error, Render};
+
+/// Render items next to each other
+///
+/// Each item gets its own column and is wrapped to fit into it. By default,
+/// columns are as wide as their content, see [`Columns::widths`] for other
+/// options. In JSON, this is an array.
+///
+/// To put items of different types next to each other, use
+/// [`Columns::column`] or box them.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::{columns, kv, text, ColumnWidth}};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target().with_width(30)?)?;
+/// out.print(
+///     columns(vec![text("Left side"), text("A longer text on the right side")])
+///         .widths(vec![ColumnWidth::Fixed(5), ColumnWidth::Ratio(1)]),
+/// )?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "\
+/// # Left   A longer text on the
+/// # side   right side
+/// # ");
+/// # Ok(()) }
+/// ```
+pub fn columns<T, I>(items: I) -> Columns
+where
+    T: Render + 'static,
+    I: IntoIterator<Item = T>,
+{
+    items.into_iter().fold(
+        Columns {
+            items: Vec::new(),
+            widths: Vec::new(),
+            gap: 2,
+        },
+        Columns::column,
+    )
+}
+
+pub struct Columns {
+    items: Vec<Box<dyn Render>>,
+    widths: Vec<ColumnWidth>,
+    gap: usize,
+}
+
+/// How wide a column is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnWidth {
+    /// As wide as the content, but shrunk to fit if necessary
+    Auto,
+    /// Exactly this many columns
+    Fixed(usize),
+    /// A share of the width that's left after the other columns, relative to
+    /// the other ratio columns
+    ///
+    /// If the target's width is unknown, this is the same as `Auto`.
+    Ratio(u16),
+}
+
+impl Columns {
+    /// Add another column
+    pub fn column<T: Render + 'static>(mut self, item: T) -> Self {
+        self.items.push(Box::new(item));
+        self.widths.push(ColumnWidth::Auto);
+        self
+    }
+
+    /// Set the widths of the columns, in order
+    ///
+    /// Columns without a width in here keep theirs.
+    pub fn widths<I: IntoIterator<Item = ColumnWidth>>(mut self, widths: I) -> Self {
+        for (current, width) in self.widths.iter_mut().zip(widths) {
+            *current = width;
+        }
+        self
+    }
+
+    /// Set the number of spaces between columns (default: 2)
+    pub fn gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Compute the width of each column, given the content's natural width
+    fn layout(&self, natural: &[usize], available: Option<usize>) -> Vec<usize> {
+        let available = match available {
+            Some(available) => available.saturating_sub(self.gap * natural.len().saturating_sub(1)),
+            None => {
+                return self
+                    .widths
+                    .iter()
+                    .zip(natural)
+                    .map(|(width, &natural)| match width {
+                        ColumnWidth::Fixed(width) => *width,
+                        _ => natural,
+                    })
+                    .collect();
+            }
+        };
+
+        let fixed: usize = self
+            .widths
+            .iter()
+            .map(|width| match width {
+                ColumnWidth::Fixed(width) => *width,
+                _ => 0,
+            })
+            .sum();
+        let ratios: usize = self
+            .widths
+            .iter()
+            .map(|width| match width {
+                ColumnWidth::Ratio(ratio) => usize::from(*ratio),
+                _ => 0,
+            })
+            .sum();
+
+        // If the auto columns don't fit, shrink the widest ones first
+        let flexible = available.saturating_sub(fixed);
+        let mut cap = natural.iter().copied().max().unwrap_or(0);
+        let capped = |cap: usize| -> usize {
+            self.widths
+                .iter()
+                .zip(natural)
+                .filter(|(width, _)| **width == ColumnWidth::Auto)
+                .map(|(_, &natural)| natural.min(cap))
+                .sum()
+        };
+        while cap > 1 && capped(cap) > flexible {
+            cap -= 1;
+        }
+
+        let remaining = flexible.saturating_sub(capped(cap));
+        self.widths
+            .iter()
+            .zip(natural)
+            .map(|(width, &natural)| match width {
+                ColumnWidth::Fixed(width) => *width,
+                ColumnWidth::Auto => natural.min(cap).max(1),
+                ColumnWidth::Ratio(ratio) => {
+                    (remaining * usize::from(*ratio) / ratios.max(1)).max(1)
+                }
+            })
+            .collect()
+    }
+}
+
+impl Render for Columns {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let natural = self
+            .items
+            .iter()
+            .map(|item| Ok(fmt.capture(item.as_ref())?.trim_end().width()))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let widths = self.layout(&natural, fmt.width());
+
+        let columns = self
+            .items
+            .iter()
+            .zip(&widths)
+            .map(|(item, &width)| {
+                let mut inner = fmt.clone();
+                inner.set_width(Some(width));
+                Ok(inner.capture(item.as_ref())?.trim_end().wrap(width))
+            })
+            .collect::<Result<Vec<Captured>, Error>>()?;
+
+        let rows = columns.iter().map(|c| c.lines().len()).max().unwrap_or(0);
+        for row in 0..rows {
+            if row > 0 {
+                fmt.write("\n")?;
+            }
+            let cells: Vec<_> = columns.iter().map(|c| c.lines().get(row)).collect();
+            let last = cells.iter().rposition(Option::is_some).unwrap_or(0);
+            let mut padding = 0;
+            for (cell, width) in cells.iter().zip(&widths).take(last + 1) {
+                fmt.write(" ".repeat(padding))?;
+                padding = width + self.gap;
+                if let Some(line) = cell {
+                    line.render(fmt)?;
+                    padding -= line.width().min(*width);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        let values = self
+            .items
+            .iter()
+            .map(|item| json::Formatter::capture(item.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        fmt.write(&values)
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        let ratios: u32 = self
+            .widths
+            .iter()
+            .map(|width| match width {
+                ColumnWidth::Ratio(ratio) => u32::from(*ratio),
+                _ => 0,
+            })
+            .sum();
+
+        fmt.write_raw("<table><tr>")?;
+        for (item, width) in self.items.iter().zip(&self.widths) {
+            match width {
+                ColumnWidth::Auto => fmt.write_raw("<td>")?,
+                ColumnWidth::Fixed(width) => {
+                    fmt.write_raw(format!("<td style=\"width: {}ch\">", width))?
+                }
+                ColumnWidth::Ratio(ratio) => {
+                    let percent = u32::from(*ratio) * 100 / ratios.max(1);
+                    fmt.write_raw(format!("<td style=\"width: {}%\">", percent))?
+                }
+            }
+            item.render_html(fmt)?;
+            fmt.write_raw("</td>")?;
+        }
+        fmt.write_raw("</tr></table>")
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        let cells = self
+            .items
+            .iter()
+            .map(|item| fmt.capture_inline(item.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if fmt.inline() {
+            return fmt.write_raw(cells.join(" "));
+        }
+        let header = vec![String::new(); cells.len()];
+        fmt.write_raw(markdown::table(&header, &[cells]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{columns, ColumnWidth};
+    use crate::components::{list, text};
+    use crate::{human, json, Error, Render};
+
+    #[test]
+    fn lays_out_auto_columns() -> Result<(), Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        columns(vec![text("a\nbb\nc"), text("d")])
+            .column(list().item(text("e")).item(text("f")))
+            .gap(1)
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(human_output.to_string(), "a  d - e\nbb   - f\nc");
+        Ok(())
+    }
+
+    #[test]
+    fn shares_width_by_ratio() -> Result<(), Error> {
+        let test_target = human::test();
+        let out = crate::new().add_target(test_target.target().with_width(17)?)?;
+        out.print(
+            columns(vec![text("x"), text("aaa bbb"), text("ccc ddd")]).widths(vec![
+                ColumnWidth::Fixed(2),
+                ColumnWidth::Ratio(1),
+                ColumnWidth::Ratio(2),
+            ]),
+        )?;
+        out.flush()?;
+        assert_eq!(test_target.to_string(), "x   aaa  ccc ddd\n    bbb\n");
+        Ok(())
+    }
+
+    #[test]
+    fn shrinks_auto_columns() -> Result<(), Error> {
+        let test_target = human::test();
+        let out = crate::new().add_target(test_target.target().with_width(9)?)?;
+        out.print(columns(vec![text("aaa bbb"), text("ccc")]))?;
+        out.flush()?;
+        assert_eq!(test_target.to_string(), "aaa   ccc\nbbb\n");
+        Ok(())
+    }
+
+    #[test]
+    fn renders_json_arrays() -> Result<(), Error> {
+        let json = json::test();
+        let mut fmt = json.formatter();
+        columns(vec![1, 2])
+            .column(text("x"))
+            .render_json(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(json.to_string(), "[1,2,\"x\"]");
+        Ok(())
+    }
+}
@@ -0,0 +1,675 @@
+use crate::{human, json, Error, Render};
+use serde_json::json;
+use std::ops::Range;
+use unicode_width::UnicodeWidthChar;
+
+/// Render a compiler-style diagnostic
+///
+/// For humans, this looks a lot like rustc's error messages: A header with
+/// severity, code and message, followed by a snippet of the source code with
+/// the labeled ranges underlined, and finally notes and help messages.
+///
+/// In JSON, this renders a structured form similar to rustc's
+/// `--error-format=json`, with byte as well as line/column positions (1-based,
+/// columns counted in characters) for each label.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// use convey::{human, components::{diagnostic, Severity}};
+///
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// let source = "fn main() {\n    let x: u32 = \"a\";\n}\n";
+/// out.print(
+///     diagnostic(Severity::Error, "mismatched types")
+///         .code("E0308")
+///         .source("src/main.rs", source)
+///         .primary(29..32, "expected `u32`, found `&str`")
+///         .secondary(23..26, "expected due to this")
+///         .note("strings are not numbers"),
+/// )?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "\
+/// error[E0308]: mismatched types
+///  --> src/main.rs:2:18
+///   |
+/// 2 |     let x: u32 = \"a\";
+///   |            ---   ^^^ expected `u32`, found `&str`
+///   |            |
+///   |            expected due to this
+///   |
+///   = note: strings are not numbers
+/// ");
+/// # Ok(()) }
+/// ```
+pub fn diagnostic<T: AsRef<str>>(severity: Severity, message: T) -> Diagnostic {
+    Diagnostic {
+        severity,
+        message: message.as_ref().to_string(),
+        code: None,
+        file_name: String::new(),
+        source: None,
+        labels: Vec::new(),
+        children: Vec::new(),
+    }
+}
+
+/// How severe a diagnostic is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// Something went wrong
+    Error,
+    /// Something might go wrong
+    Warning,
+    /// Additional information
+    Note,
+    /// Hints on how to fix something
+    Help,
+}
+
+impl Severity {
+    fn name(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+            Severity::Help => "help",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    severity: Severity,
+    message: String,
+    code: Option<String>,
+    file_name: String,
+    source: Option<String>,
+    labels: Vec<Label>,
+    children: Vec<(Severity, String)>,
+}
+
+#[derive(Clone, Debug)]
+struct Label {
+    range: Range<usize>,
+    text: String,
+    primary: bool,
+}
+
+impl Diagnostic {
+    /// Set an error code, like `E0308`
+    pub fn code<T: AsRef<str>>(mut self, code: T) -> Self {
+        self.code = Some(code.as_ref().to_string());
+        self
+    }
+
+    /// Set the source code the labels point into
+    pub fn source<N: AsRef<str>, S: AsRef<str>>(mut self, file_name: N, source: S) -> Self {
+        self.file_name = file_name.as_ref().to_string();
+        self.source = Some(source.as_ref().to_string());
+        self
+    }
+
+    /// Label a byte range of the source as the cause of this diagnostic
+    pub fn primary<T: AsRef<str>>(mut self, range: Range<usize>, label: T) -> Self {
+        self.labels.push(Label {
+            range,
+            text: label.as_ref().to_string(),
+            primary: true,
+        });
+        self
+    }
+
+    /// Label a byte range of the source as additional context
+    pub fn secondary<T: AsRef<str>>(mut self, range: Range<usize>, label: T) -> Self {
+        self.labels.push(Label {
+            range,
+            text: label.as_ref().to_string(),
+            primary: false,
+        });
+        self
+    }
+
+    /// Add a note
+    pub fn note<T: AsRef<str>>(mut self, text: T) -> Self {
+        self.children
+            .push((Severity::Note, text.as_ref().to_string()));
+        self
+    }
+
+    /// Add a help message
+    pub fn help<T: AsRef<str>>(mut self, text: T) -> Self {
+        self.children
+            .push((Severity::Help, text.as_ref().to_string()));
+        self
+    }
+}
+
+/// Lines of source code, with helpers to find positions in them
+struct SourceLines<'a> {
+    source: &'a str,
+    /// Byte offset of the start of each line
+    starts: Vec<usize>,
+}
+
+impl<'a> SourceLines<'a> {
+    fn new(source: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        SourceLines { source, starts }
+    }
+
+    /// Zero-based line index and byte offset in that line
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = match self.starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+        (line, offset - self.starts[line])
+    }
+
+    fn line(&self, index: usize) -> &'a str {
+        let start = self.starts[index];
+        let end = self
+            .starts
+            .get(index + 1)
+            .cloned()
+            .unwrap_or(self.source.len());
+        self.source[start..end].trim_end_matches(['\n', '\r'])
+    }
+
+    /// Zero-based column in characters
+    fn column(&self, line: usize, byte: usize) -> usize {
+        let text = self.line(line);
+        text[..byte.min(text.len())].chars().count()
+    }
+
+    /// Column when displaying the line, with tabs expanded and wide
+    /// characters taking up two columns
+    fn display_column(&self, line: usize, byte: usize) -> usize {
+        let text = self.line(line);
+        text.char_indices()
+            .take_while(|&(index, _)| index < byte)
+            .map(|(_, c)| if c == '\t' { 4 } else { c.width().unwrap_or(0) })
+            .sum()
+    }
+}
+
+/// A label (or part of one) on a single line, in display columns
+struct Annotation<'a> {
+    start: usize,
+    end: usize,
+    primary: bool,
+    text: Option<&'a str>,
+}
+
+impl Diagnostic {
+    fn severity_style(&self) -> &'static str {
+        self.severity.name()
+    }
+
+    fn annotations<'a>(&'a self, lines: &SourceLines<'_>) -> Vec<(usize, Annotation<'a>)> {
+        let mut annotations = Vec::new();
+        for label in &self.labels {
+            let (start_line, start) = lines.position(label.range.start);
+            let (end_line, end) = lines.position(label.range.end.max(label.range.start));
+            let text = Some(label.text.as_str()).filter(|t| !t.is_empty());
+
+            if start_line == end_line {
+                let start = lines.display_column(start_line, start);
+                let end = lines.display_column(end_line, end).max(start + 1);
+                annotations.push((
+                    start_line,
+                    Annotation {
+                        start,
+                        end,
+                        primary: label.primary,
+                        text,
+                    },
+                ));
+            } else {
+                // Multi-line ranges are marked until the end of their first
+                // line and from the start of their last line, which also gets
+                // the label.
+                let first = lines.line(start_line);
+                let last = lines.line(end_line);
+                let indent = last.len() - last.trim_start().len();
+                let first_start = lines.display_column(start_line, start);
+                annotations.push((
+                    start_line,
+                    Annotation {
+                        start: first_start,
+                        end: lines
+                            .display_column(start_line, first.len())
+                            .max(first_start + 1),
+                        primary: label.primary,
+                        text: None,
+                    },
+                ));
+                // The range may end before the indentation of its last line
+                let last_start = lines.display_column(end_line, indent.min(end));
+                annotations.push((
+                    end_line,
+                    Annotation {
+                        start: last_start,
+                        end: lines.display_column(end_line, end).max(last_start + 1),
+                        primary: label.primary,
+                        text,
+                    },
+                ));
+            }
+        }
+        annotations
+    }
+
+    fn render_snippet(
+        &self,
+        fmt: &mut human::Formatter,
+        source: &str,
+        gutter_width: usize,
+    ) -> Result<(), Error> {
+        let lines = SourceLines::new(source);
+        let annotations = self.annotations(&lines);
+        let mut line_numbers: Vec<usize> = annotations.iter().map(|(line, _)| *line).collect();
+        line_numbers.sort();
+        line_numbers.dedup();
+
+        let gutter = |fmt: &mut human::Formatter, line| write_gutter(fmt, gutter_width, line);
+
+        gutter(fmt, None)?;
+        let mut previous: Option<usize> = None;
+        for &line in &line_numbers {
+            match previous {
+                Some(previous) if line == previous + 2 => {
+                    fmt.write("\n")?;
+                    gutter(fmt, Some(previous + 1))?;
+                    fmt.write(format!(
+                        " {}",
+                        lines.line(previous + 1).replace('\t', "    ")
+                    ))?;
+                }
+                Some(previous) if line > previous + 2 => {
+                    fmt.write("\n")?;
+                    fmt.write_styled("gutter", "...")?;
+                }
+                _ => {}
+            }
+            previous = Some(line);
+
+            fmt.write("\n")?;
+            gutter(fmt, Some(line))?;
+            fmt.write(format!(" {}", lines.line(line).replace('\t', "    ")))?;
+
+            let mut on_line: Vec<&Annotation<'_>> = annotations
+                .iter()
+                .filter(|(l, _)| *l == line)
+                .map(|(_, a)| a)
+                .collect();
+            on_line.sort_by_key(|a| (a.start, !a.primary));
+            self.render_annotations(fmt, &on_line, gutter_width)?;
+        }
+
+        Ok(())
+    }
+
+    fn render_annotations(
+        &self,
+        fmt: &mut human::Formatter,
+        annotations: &[&Annotation<'_>],
+        gutter_width: usize,
+    ) -> Result<(), Error> {
+        let gutter = |fmt: &mut human::Formatter| write_gutter(fmt, gutter_width, None);
+        let style = |a: &Annotation<'_>| {
+            if a.primary {
+                self.severity_style()
+            } else {
+                "gutter"
+            }
+        };
+
+        // The line with all the underlines, and the label of the rightmost one
+        fmt.write("\n")?;
+        gutter(fmt)?;
+        fmt.write(" ")?;
+        let mut column = 0;
+        for a in annotations {
+            if a.end <= column {
+                continue;
+            }
+            let start = a.start.max(column);
+            fmt.write(" ".repeat(start - column))?;
+            let marker = if a.primary { "^" } else { "-" };
+            fmt.write_styled(style(a), marker.repeat(a.end - start))?;
+            column = a.end;
+        }
+        let inline = annotations.last().filter(|a| a.text.is_some());
+        if let Some(a) = inline {
+            fmt.write(" ")?;
+            fmt.write_styled(style(a), a.text.unwrap_or_default())?;
+        }
+
+        // All other labels get their own line, connected to their underline
+        let mut pending: Vec<&Annotation<'_>> = annotations
+            .iter()
+            .cloned()
+            .filter(|a| a.text.is_some())
+            .collect();
+        if inline.is_some() {
+            pending.pop();
+        }
+        while let Some(current) = pending.last().cloned() {
+            for with_label in &[false, true] {
+                fmt.write("\n")?;
+                gutter(fmt)?;
+                fmt.write(" ")?;
+                let mut column = 0;
+                for a in &pending {
+                    if a.start < column {
+                        continue;
+                    }
+                    fmt.write(" ".repeat(a.start - column))?;
+                    if *with_label && a.start == current.start {
+                        fmt.write_styled(style(a), current.text.unwrap_or_default())?;
+                        break;
+                    }
+                    fmt.write_styled(style(a), "|")?;
+                    column = a.start + 1;
+                }
+            }
+            pending.pop();
+        }
+
+        Ok(())
+    }
+}
+
+/// Write the gutter, containing the line number if there is one
+fn write_gutter(
+    fmt: &mut human::Formatter,
+    width: usize,
+    line: Option<usize>,
+) -> Result<(), Error> {
+    let number = line.map(|n| (n + 1).to_string()).unwrap_or_default();
+    fmt.write_styled("gutter", format!("{:>w$} |", number, w = width))
+}
+
+impl Render for Diagnostic {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let severity = self.severity.name();
+        match &self.code {
+            Some(code) => fmt.write_styled(severity, format!("{}[{}]", severity, code))?,
+            None => fmt.write_styled(severity, severity)?,
+        }
+        fmt.write_styled("emphasis", format!(": {}", self.message))?;
+
+        let source = self.source.as_ref().filter(|_| !self.labels.is_empty());
+        let gutter_width = match source {
+            Some(source) => {
+                let lines = SourceLines::new(source);
+                let last_line = self
+                    .labels
+                    .iter()
+                    .map(|l| lines.position(l.range.end.max(l.range.start)).0 + 1)
+                    .max()
+                    .unwrap_or(1);
+                last_line.to_string().len()
+            }
+            None => 0,
+        };
+
+        if let Some(source) = source {
+            let lines = SourceLines::new(source);
+            let main = self
+                .labels
+                .iter()
+                .find(|l| l.primary)
+                .unwrap_or(&self.labels[0]);
+            let (line, byte) = lines.position(main.range.start);
+            fmt.write("\n")?;
+            fmt.write_styled("gutter", format!("{:w$}--> ", "", w = gutter_width))?;
+            fmt.write(format!(
+                "{}:{}:{}",
+                self.file_name,
+                line + 1,
+                lines.column(line, byte) + 1
+            ))?;
+            fmt.write("\n")?;
+            self.render_snippet(fmt, source, gutter_width)?;
+        }
+
+        if !self.children.is_empty() && source.is_some() {
+            fmt.write("\n")?;
+            fmt.write_styled("gutter", format!("{:w$} |", "", w = gutter_width))?;
+        }
+        for (severity, text) in &self.children {
+            fmt.write("\n")?;
+            fmt.write_styled("gutter", format!("{:w$} = ", "", w = gutter_width))?;
+            fmt.write_styled("emphasis", format!("{}:", severity.name()))?;
+            let indent = " ".repeat(gutter_width + severity.name().len() + 4);
+            for (i, line) in text.lines().enumerate() {
+                if i > 0 {
+                    fmt.write(format!("\n{}", indent))?;
+                }
+                fmt.write(format!(" {}", line))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        let lines = self.source.as_ref().map(|s| SourceLines::new(s));
+        let spans: Vec<_> = match &lines {
+            Some(lines) => self
+                .labels
+                .iter()
+                .map(|label| {
+                    let (line_start, start) = lines.position(label.range.start);
+                    let (line_end, end) = lines.position(label.range.end);
+                    json!({
+                        "file_name": self.file_name,
+                        "byte_start": label.range.start,
+                        "byte_end": label.range.end,
+                        "line_start": line_start + 1,
+                        "line_end": line_end + 1,
+                        "column_start": lines.column(line_start, start) + 1,
+                        "column_end": lines.column(line_end, end) + 1,
+                        "is_primary": label.primary,
+                        "label": Some(&label.text).filter(|t| !t.is_empty()),
+                    })
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let children: Vec<_> = self
+            .children
+            .iter()
+            .map(|(severity, message)| {
+                json!({
+                    "message": message,
+                    "code": null,
+                    "level": severity.name(),
+                    "spans": [],
+                    "children": [],
+                })
+            })
+            .collect();
+
+        fmt.write(&json!({
+            "message": self.message,
+            "code": self.code,
+            "level": self.severity.name(),
+            "spans": spans,
+            "children": children,
+        }))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{diagnostic, Severity};
+    use crate::{human, json, Error, Render};
+
+    const SOURCE: &str =
+        "fn main() {\n    let x = 1;\n    foo(x, y);\n\n\n    bar(\n        x,\n    );\n}\n";
+
+    fn render(item: &impl Render) -> Result<String, Error> {
+        let human_output = human::test();
+        item.render_for_humans(&mut human_output.formatter())?;
+        Ok(human_output.to_string())
+    }
+
+    #[test]
+    fn renders_message_only() -> Result<(), Error> {
+        let item = diagnostic(Severity::Warning, "unused crate").help("remove it\nor use it");
+        assert_eq!(
+            render(&item)?,
+            "warning: unused crate\n\
+             \x20= help: remove it\n\
+             \x20        or use it"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_labels_on_multiple_lines() -> Result<(), Error> {
+        let item = diagnostic(Severity::Error, "cannot find value `y`")
+            .source("main.rs", SOURCE)
+            .secondary(20..21, "")
+            .primary(38..39, "not found")
+            .secondary(35..36, "first argument")
+            .secondary(31..34, "function")
+            .note("see the docs");
+
+        assert_eq!(
+            render(&item)?,
+            "error: cannot find value `y`\n\
+             \x20--> main.rs:3:12\n\
+             \x20 |\n\
+             2 |     let x = 1;\n\
+             \x20 |         -\n\
+             3 |     foo(x, y);\n\
+             \x20 |     --- -  ^ not found\n\
+             \x20 |     |   |\n\
+             \x20 |     |   first argument\n\
+             \x20 |     |\n\
+             \x20 |     function\n\
+             \x20 |\n\
+             \x20 = note: see the docs"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_multiline_labels_and_gaps() -> Result<(), Error> {
+        let item = diagnostic(Severity::Error, "oops")
+            .code("E0001")
+            .source("main.rs", SOURCE)
+            .primary(20..21, "here")
+            .primary(48..70, "and here");
+
+        assert_eq!(
+            render(&item)?,
+            "error[E0001]: oops\n\
+             \x20--> main.rs:2:9\n\
+             \x20 |\n\
+             2 |     let x = 1;\n\
+             \x20 |         ^ here\n\
+             ...\n\
+             6 |     bar(\n\
+             \x20 |     ^^^^\n\
+             7 |         x,\n\
+             8 |     );\n\
+             \x20 |     ^^ and here"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn marks_ranges_ending_in_the_indentation() -> Result<(), Error> {
+        let item = diagnostic(Severity::Error, "oops")
+            .source("main.rs", "fn main() {\n    let x = 1;\n}\n")
+            .primary(0..12, "here");
+        assert_eq!(
+            render(&item)?,
+            "error: oops\n\
+             \x20--> main.rs:1:1\n\
+             \x20 |\n\
+             1 | fn main() {\n\
+             \x20 | ^^^^^^^^^^^\n\
+             2 |     let x = 1;\n\
+             \x20 | ^ here"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lines_up_markers_with_wide_characters() -> Result<(), Error> {
+        let item = diagnostic(Severity::Error, "oops")
+            .source("main.rs", "let 名前 = 1;\n")
+            .primary(4..10, "name")
+            .secondary(13..14, "value");
+
+        assert_eq!(
+            render(&item)?,
+            "error: oops\n\
+             \x20--> main.rs:1:5\n\
+             \x20 |\n\
+             1 | let 名前 = 1;\n\
+             \x20 |     ^^^^   - value\n\
+             \x20 |     |\n\
+             \x20 |     name"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_json() -> Result<(), Error> {
+        let item = diagnostic(Severity::Error, "oops")
+            .code("E0001")
+            .source("main.rs", SOURCE)
+            .primary(38..39, "here")
+            .help("try harder");
+
+        let json = json::test();
+        item.render_json(&mut json.formatter())?;
+        let value: serde_json::Value = serde_json::from_str(&json.to_string())?;
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "message": "oops",
+                "code": "E0001",
+                "level": "error",
+                "spans": [{
+                    "file_name": "main.rs",
+                    "byte_start": 38,
+                    "byte_end": 39,
+                    "line_start": 3,
+                    "line_end": 3,
+                    "column_start": 12,
+                    "column_end": 13,
+                    "is_primary": true,
+                    "label": "here",
+                }],
+                "children": [{
+                    "message": "try harder",
+                    "code": null,
+                    "level": "help",
+                    "spans": [],
+                    "children": [],
+                }],
+            })
+        );
+        Ok(())
+    }
+}
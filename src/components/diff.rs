@@ -0,0 +1,405 @@
+use crate::layout::{split_at_width, width};
+use crate::{human, json, Error, Render};
+use serde_json::json;
+use similar::udiff::UnifiedHunkHeader;
+use similar::{ChangeTag, DiffOp, TextDiff};
+use std::ops::Range;
+
+/// Render the differences between two texts
+///
+/// For humans, this looks like the output of `diff -u`: changed lines are
+/// shown in hunks, with a few lines of context around them. In JSON, this is
+/// an object with a list of `hunks`, which contain the changed lines.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::diff};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(diff("port = 80\nhost = example.com\n", "port = 8080\nhost = example.com\n"))?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "\
+/// # @@ -1,2 +1,2 @@
+/// # -port = 80
+/// # +port = 8080
+/// #  host = example.com
+/// # ");
+/// # Ok(()) }
+/// ```
+pub fn diff<O: AsRef<str>, N: AsRef<str>>(old: O, new: N) -> Diff {
+    Diff {
+        old: old.as_ref().to_string(),
+        new: new.as_ref().to_string(),
+        context: 3,
+        words: false,
+        side_by_side: false,
+    }
+}
+
+pub struct Diff {
+    old: String,
+    new: String,
+    context: usize,
+    words: bool,
+    side_by_side: bool,
+}
+
+/// A line of the diff, split into parts that are emphasized or not
+struct Line {
+    tag: ChangeTag,
+    old_index: Option<usize>,
+    new_index: Option<usize>,
+    parts: Vec<(bool, String)>,
+}
+
+impl Line {
+    fn text(&self) -> String {
+        self.parts.iter().map(|(_, text)| text.as_str()).collect()
+    }
+}
+
+impl Diff {
+    /// Set the number of unchanged lines shown around changes (default: 3)
+    pub fn context(mut self, lines: usize) -> Self {
+        self.context = lines;
+        self
+    }
+
+    /// Highlight the words that changed within changed lines
+    pub fn words(mut self, yes: bool) -> Self {
+        self.words = yes;
+        self
+    }
+
+    /// Show old and new text next to each other instead of one after another
+    ///
+    /// The two sides share the width of the target (or 80 columns, if it is
+    /// unknown), longer lines are cut off.
+    pub fn side_by_side(mut self, yes: bool) -> Self {
+        self.side_by_side = yes;
+        self
+    }
+
+    fn lines<'a>(&self, diff: &'a TextDiff<'a, 'a, 'a, str>, op: &DiffOp) -> Vec<Line> {
+        let trim = |text: &str| text.trim_end_matches(['\n', '\r']).to_string();
+        if self.words {
+            diff.iter_inline_changes(op)
+                .map(|change| Line {
+                    tag: change.tag(),
+                    old_index: change.old_index(),
+                    new_index: change.new_index(),
+                    parts: change
+                        .iter_strings_lossy()
+                        .map(|(emphasized, text)| (emphasized, trim(&text)))
+                        .collect(),
+                })
+                .collect()
+        } else {
+            diff.iter_changes(op)
+                .map(|change| Line {
+                    tag: change.tag(),
+                    old_index: change.old_index(),
+                    new_index: change.new_index(),
+                    parts: vec![(false, trim(change.value()))],
+                })
+                .collect()
+        }
+    }
+
+    fn render_unified(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let diff = text_diff(&self.old, &self.new);
+        let mut first = true;
+        for group in diff.grouped_ops(self.context) {
+            if !first {
+                fmt.write("\n")?;
+            }
+            first = false;
+            fmt.write_styled("diff.hunk", UnifiedHunkHeader::new(&group).to_string())?;
+
+            for op in &group {
+                for line in self.lines(&diff, op) {
+                    fmt.write("\n")?;
+                    let (sign, style) = style(line.tag);
+                    write_part(fmt, style, false, sign)?;
+                    for (emphasized, text) in &line.parts {
+                        write_part(fmt, style, *emphasized, text)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn render_side_by_side(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let separator = width(fmt.glyphs().vertical) + 2;
+        let column = fmt.width().unwrap_or(80).saturating_sub(separator) / 2;
+        let diff = text_diff(&self.old, &self.new);
+        let mut first = true;
+        for group in diff.grouped_ops(self.context) {
+            if !first {
+                fmt.write("\n")?;
+            }
+            first = false;
+            fmt.write_styled("diff.hunk", UnifiedHunkHeader::new(&group).to_string())?;
+
+            for op in &group {
+                let lines = self.lines(&diff, op);
+                let (left, right): (Vec<_>, Vec<_>) = match op {
+                    DiffOp::Equal { .. } => lines.iter().map(|l| (Some(l), Some(l))).unzip(),
+                    _ => {
+                        let deleted: Vec<_> = lines
+                            .iter()
+                            .filter(|l| l.tag == ChangeTag::Delete)
+                            .collect();
+                        let inserted: Vec<_> = lines
+                            .iter()
+                            .filter(|l| l.tag == ChangeTag::Insert)
+                            .collect();
+                        (0..deleted.len().max(inserted.len()))
+                            .map(|i| (deleted.get(i).copied(), inserted.get(i).copied()))
+                            .unzip()
+                    }
+                };
+
+                for (left, right) in left.into_iter().zip(right) {
+                    fmt.write("\n")?;
+                    let used = write_cell(fmt, left, column)?;
+                    fmt.write(" ".repeat(column.saturating_sub(used)))?;
+                    // Nothing is padded after the separator if the right side
+                    // is empty
+                    let separator = match right {
+                        Some(_) => format!(" {} ", fmt.glyphs().vertical),
+                        None => format!(" {}", fmt.glyphs().vertical),
+                    };
+                    fmt.write_styled("rule", separator)?;
+                    write_cell(fmt, right, column)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn text_diff<'a>(old: &'a str, new: &'a str) -> TextDiff<'a, 'a, 'a, str> {
+    TextDiff::from_lines(old, new)
+}
+
+/// The first line of a hunk's range, like in its `@@` header
+///
+/// An empty range starts at the line it follows, or 0 at the start of a file.
+fn hunk_start(range: &Range<usize>) -> usize {
+    if range.is_empty() {
+        range.start
+    } else {
+        range.start + 1
+    }
+}
+
+fn style(tag: ChangeTag) -> (&'static str, Option<&'static str>) {
+    match tag {
+        ChangeTag::Equal => (" ", None),
+        ChangeTag::Delete => ("-", Some("diff.delete")),
+        ChangeTag::Insert => ("+", Some("diff.insert")),
+    }
+}
+
+fn write_part(
+    fmt: &mut human::Formatter,
+    style: Option<&str>,
+    emphasized: bool,
+    text: &str,
+) -> Result<(), Error> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    match (style, emphasized) {
+        (Some(style), true) => fmt.write_styled(&format!("{}.word", style), text),
+        (Some(style), false) => fmt.write_styled(style, text),
+        (None, _) => fmt.write(text),
+    }
+}
+
+/// Write a line of one side of a side by side diff, cut off at `column`
+///
+/// Returns the width of what was written.
+fn write_cell(
+    fmt: &mut human::Formatter,
+    line: Option<&Line>,
+    column: usize,
+) -> Result<usize, Error> {
+    let line = match line {
+        Some(line) => line,
+        None => return Ok(0),
+    };
+    let (sign, style) = style(line.tag);
+    let mut used = 0;
+    let sign = (false, sign.to_string());
+    let parts = std::iter::once(&sign).chain(&line.parts);
+    for (emphasized, text) in parts {
+        if used >= column {
+            break;
+        }
+        // A wide character that doesn't fit anymore is left out, the rest of
+        // the cell is padded by the caller
+        let (text, rest) = split_at_width(text, column - used);
+        write_part(fmt, style, *emphasized, text)?;
+        used += width(text);
+        if !rest.is_empty() {
+            break;
+        }
+    }
+    Ok(used)
+}
+
+impl Render for Diff {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        if self.side_by_side {
+            self.render_side_by_side(fmt)
+        } else {
+            self.render_unified(fmt)
+        }
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        let diff = text_diff(&self.old, &self.new);
+        let hunks: Vec<_> = diff
+            .grouped_ops(self.context)
+            .iter()
+            .map(|group| {
+                let old = group[0].old_range().start..group[group.len() - 1].old_range().end;
+                let new = group[0].new_range().start..group[group.len() - 1].new_range().end;
+                let changes: Vec<_> = group
+                    .iter()
+                    .flat_map(|op| self.lines(&diff, op))
+                    .map(|line| {
+                        let op = match line.tag {
+                            ChangeTag::Equal => "equal",
+                            ChangeTag::Delete => "delete",
+                            ChangeTag::Insert => "insert",
+                        };
+                        json!({
+                            "op": op,
+                            "old_line": line.old_index.map(|i| i + 1),
+                            "new_line": line.new_index.map(|i| i + 1),
+                            "text": line.text(),
+                        })
+                    })
+                    .collect();
+                json!({
+                    "old_start": hunk_start(&old),
+                    "old_lines": old.len(),
+                    "new_start": hunk_start(&new),
+                    "new_lines": new.len(),
+                    "changes": changes,
+                })
+            })
+            .collect();
+        fmt.write(&json!({ "hunks": hunks }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::diff;
+    use crate::{human, json, Error, Render};
+
+    const OLD: &str = "a\nb\nc\nd\ne\nf\ng\nh\n";
+    const NEW: &str = "a\nB\nc\nd\ne\nf\ng\nh\ni\n";
+
+    #[test]
+    fn renders_hunks_with_context() -> Result<(), Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        diff(OLD, NEW).context(1).render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            human_output.to_string(),
+            "@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n@@ -8 +8,2 @@\n h\n+i"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn highlights_changed_words() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let mut fmt = test_target.formatter();
+        diff("let x = 1;\n", "let x = 2;\n")
+            .words(true)
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        let output = test_target.to_string();
+        assert!(output.contains("\u{1b}[0m\u{1b}[30m\u{1b}[41m1;\u{1b}[0m"));
+        assert!(output.contains("\u{1b}[0m\u{1b}[30m\u{1b}[42m2;\u{1b}[0m"));
+        Ok(())
+    }
+
+    #[test]
+    fn renders_side_by_side() -> Result<(), Error> {
+        let test_target = human::test();
+        let out = crate::new().add_target(test_target.target().with_width(23)?)?;
+        out.print(
+            diff("same\nold line\nremoved\n", "same\nnew line that is long\n").side_by_side(true),
+        )?;
+        out.flush()?;
+        assert_eq!(
+            test_target.to_string(),
+            "@@ -1,3 +1,2 @@\n\
+             \x20same      │  same\n\
+             -old line  │ +new line \n\
+             -removed   │\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn pads_cells_instead_of_splitting_wide_characters() -> Result<(), Error> {
+        let test_target = human::test();
+        let out = crate::new().add_target(test_target.target().with_width(9)?)?;
+        out.print(diff("日本語\n", "x日本語\n").side_by_side(true))?;
+        out.flush()?;
+        assert_eq!(
+            test_target.to_string(),
+            "@@ -1 +1 @@\n\
+             -日 │ +x\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_json_hunks() -> Result<(), Error> {
+        let json = json::test();
+        let mut fmt = json.formatter();
+        diff("a\nb\n", "a\nc\n").context(0).render_json(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            json.to_string(),
+            "{\"hunks\":[{\"changes\":[\
+             {\"new_line\":null,\"old_line\":2,\"op\":\"delete\",\"text\":\"b\"},\
+             {\"new_line\":2,\"old_line\":null,\"op\":\"insert\",\"text\":\"c\"}\
+             ],\"new_lines\":1,\"new_start\":2,\"old_lines\":1,\"old_start\":2}]}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn starts_empty_json_ranges_like_the_hunk_header() -> Result<(), Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        diff("", "a\n").render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(human_output.to_string(), "@@ -0,0 +1 @@\n+a");
+
+        let json = json::test();
+        let mut fmt = json.formatter();
+        diff("", "a\n").render_json(&mut fmt)?;
+        diff("a\nb\n", "a\n").context(0).render_json(&mut fmt)?;
+        fmt.flush()?;
+        let output = json.to_string();
+        assert!(output.contains("\"new_lines\":1,\"new_start\":1,\"old_lines\":0,\"old_start\":0"));
+        assert!(output.contains("\"new_lines\":0,\"new_start\":1,\"old_lines\":1,\"old_start\":2"));
+        Ok(())
+    }
+}
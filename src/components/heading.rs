@@ -0,0 +1,289 @@
+use crate::glyphs;
+use crate::layout::width;
+use crate::{html, human, json, markdown, Error, Render};
+use serde_json::{json, Value};
+
+/// Render a heading
+///
+/// Titles of level 1 and 2 are underlined with `=` and `-`, respectively,
+/// all others are just written with the theme's `heading` style. Level 0 is
+/// treated as level 1. In JSON, this is an object like
+/// `{"heading": "Title", "level": 1}`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::{heading, text}};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(heading(1, text("Summary")))?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "Summary\n=======\n");
+/// # Ok(()) }
+/// ```
+pub fn heading<T: Render + 'static>(level: u8, title: T) -> Heading {
+    Heading {
+        level: level.max(1),
+        title: Box::new(title),
+    }
+}
+
+pub struct Heading {
+    level: u8,
+    title: Box<dyn Render>,
+}
+
+impl Render for Heading {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let title = fmt.capture(self.title.as_ref())?.trim_end();
+        match fmt.theme().get("heading").cloned() {
+            Some(style) => {
+                for (i, line) in title.lines().iter().enumerate() {
+                    if i > 0 {
+                        fmt.write("\n")?;
+                    }
+                    fmt.set_style(&style)?;
+                    line.render(fmt)?;
+                    fmt.reset()?;
+                }
+            }
+            None => title.render(fmt)?,
+        }
+
+        let underline = match self.level {
+            1 => "=",
+            2 => "-",
+            _ => return Ok(()),
+        };
+        fmt.write("\n")?;
+        fmt.write_styled("heading", underline.repeat(title.width()))
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(&json!({
+            "heading": json::Formatter::capture(self.title.as_ref())?,
+            "level": self.level,
+        }))
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        let level = self.level.clamp(1, 6);
+        fmt.write_raw(format!("<h{}>", level))?;
+        self.title.render_html(fmt)?;
+        fmt.write_raw(format!("</h{}>", level))
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        let title = fmt.capture_inline(self.title.as_ref())?;
+        if fmt.inline() {
+            return fmt.write_raw(format!("**{}**", title));
+        }
+        let level = usize::from(self.level.clamp(1, 6));
+        fmt.write_raw(format!("{} {}", "#".repeat(level), title))
+    }
+}
+
+/// Render a horizontal line across the whole width of the target
+///
+/// If the width is unknown, the line is 80 columns wide. In JSON, this is
+/// `{"rule": true}`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::rule};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target().with_width(10)?)?;
+/// out.print(rule())?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "──────────\n");
+/// # Ok(()) }
+/// ```
+pub fn rule() -> Rule {
+    Rule { ascii: None }
+}
+
+pub struct Rule {
+    ascii: Option<bool>,
+}
+
+impl Rule {
+    /// Draw the line with `-` instead of a box drawing character
+    ///
+    /// By default, this depends on what the target supports.
+    pub fn ascii(mut self, yes: bool) -> Self {
+        self.ascii = Some(yes);
+        self
+    }
+}
+
+impl Render for Rule {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let line = glyphs::select(fmt, self.ascii).horizontal;
+        let columns = fmt.width().unwrap_or(80) / width(line);
+        fmt.write_styled("rule", line.repeat(columns))
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(&json!({ "rule": true }))
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        fmt.write_raw("<hr>")
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        fmt.write_raw("---")
+    }
+}
+
+/// Render a titled section
+///
+/// The title is rendered as a [`heading`] (of level 1, by default), followed
+/// by the body. In JSON, this is an object like
+/// `{"section": "Title", "items": [...]}`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::{section, text}};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(
+///     section(text("Results"), text("All tests passed."))
+///         .level(2)
+///         .item(text("Took 3 seconds.")),
+/// )?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "\
+/// # Results
+/// # -------
+/// # All tests passed.
+/// # Took 3 seconds.
+/// # ");
+/// # Ok(()) }
+/// ```
+pub fn section<T: Render + 'static, B: Render + 'static>(title: T, body: B) -> Section {
+    Section {
+        title: heading(1, title),
+        items: vec![Box::new(body)],
+    }
+}
+
+pub struct Section {
+    title: Heading,
+    items: Vec<Box<dyn Render>>,
+}
+
+impl Section {
+    /// Set the level of the section's heading (level 0 is treated as 1)
+    pub fn level(mut self, level: u8) -> Self {
+        self.title.level = level.max(1);
+        self
+    }
+
+    /// Add another item to the body
+    pub fn item<T: Render + 'static>(mut self, item: T) -> Self {
+        self.items.push(Box::new(item));
+        self
+    }
+}
+
+impl Render for Section {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        self.title.render_for_humans(fmt)?;
+        for item in &self.items {
+            fmt.write("\n")?;
+            item.render_for_humans(fmt)?;
+        }
+        Ok(())
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        let items = self
+            .items
+            .iter()
+            .map(|item| json::Formatter::capture(item.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        fmt.write(&json!({
+            "section": json::Formatter::capture(self.title.title.as_ref())?,
+            "level": self.title.level,
+            "items": Value::Array(items),
+        }))
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        fmt.write_raw("<section>")?;
+        self.title.render_html(fmt)?;
+        for item in &self.items {
+            fmt.write_raw("<div>")?;
+            item.render_html(fmt)?;
+            fmt.write_raw("</div>")?;
+        }
+        fmt.write_raw("</section>")
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        self.title.render_markdown(fmt)?;
+        for item in &self.items {
+            fmt.write_raw("\n\n")?;
+            item.render_markdown(fmt)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{heading, rule, section};
+    use crate::components::{kv, text};
+    use crate::{human, json, Error};
+
+    #[test]
+    fn renders_headings() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let out = crate::new().add_target(test_target.target())?;
+        out.print(heading(2, text("Größe")))?;
+        out.print(heading(3, text("x")))?;
+        out.flush()?;
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}[0m\u{1b}[1mGröße\u{1b}[0m\n\u{1b}[0m\u{1b}[1m-----\u{1b}[0m\n\
+             \u{1b}[0m\u{1b}[1mx\u{1b}[0m\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn adapts_rules_to_width() -> Result<(), Error> {
+        let test_target = human::test();
+        let out = crate::new().add_target(test_target.target().with_width(5)?)?;
+        out.print(rule().ascii(true))?;
+        out.flush()?;
+        assert_eq!(test_target.to_string(), "-----\n");
+        Ok(())
+    }
+
+    #[test]
+    fn renders_sections_as_json() -> Result<(), Error> {
+        let json = json::test();
+        let out = crate::new().add_target(json.target())?;
+        out.print(rule())?;
+        out.print(section(text("Stats"), kv().entry("runs", 3)).item(text("done")))?;
+        out.print(heading(0, text("Zero")))?;
+        out.flush()?;
+        assert_eq!(
+            json.to_string(),
+            "{\"rule\":true}\n\
+             {\"items\":[{\"runs\":3},\"done\"],\"level\":1,\"section\":\"Stats\"}\n\
+             {\"heading\":\"Zero\",\"level\":1}\n"
+        );
+        Ok(())
+    }
+}
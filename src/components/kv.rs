@@ -0,0 +1,273 @@
+use crate::layout::width;
+use crate::{html, human, json, markdown, Error, Render};
+use serde_json::{Map, Value};
+
+/// Render a list of keys and values
+///
+/// For humans, the keys are aligned so that the values start in the same
+/// column. Values that span multiple lines are indented to that column as
+/// well. In JSON, this is an object.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::{kv, text}};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(
+///     kv().entry("name", text("convey"))
+///         .entry("version", text("0.2.0"))
+///         .section("authors", kv().entry("lead", text("Pascal"))),
+/// )?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "\
+/// # name:    convey
+/// # version: 0.2.0
+/// # authors:
+/// #   lead: Pascal
+/// # ");
+/// # Ok(()) }
+/// ```
+pub fn kv() -> KeyValue {
+    KeyValue {
+        entries: Vec::new(),
+        separator: String::from(":"),
+        key_style: None,
+    }
+}
+
+pub struct KeyValue {
+    entries: Vec<Entry>,
+    separator: String,
+    key_style: Option<String>,
+}
+
+enum Entry {
+    Value(String, Box<dyn Render>),
+    Section(String, KeyValue),
+}
+
+impl KeyValue {
+    /// Add a key and its value
+    pub fn entry<K: AsRef<str>, V: Render + 'static>(mut self, key: K, value: V) -> Self {
+        self.entries
+            .push(Entry::Value(key.as_ref().to_string(), Box::new(value)));
+        self
+    }
+
+    /// Add a nested list of keys and values
+    ///
+    /// For humans, the section's entries are written below its key, indented
+    /// by two spaces. In JSON, they become a nested object.
+    pub fn section<K: AsRef<str>>(mut self, key: K, entries: KeyValue) -> Self {
+        self.entries
+            .push(Entry::Section(key.as_ref().to_string(), entries));
+        self
+    }
+
+    /// Set what is written between keys and values (default: `:`)
+    pub fn separator<S: AsRef<str>>(mut self, separator: S) -> Self {
+        self.separator = separator.as_ref().to_string();
+        self
+    }
+
+    /// Write keys with a named style from the theme
+    pub fn key_style<S: AsRef<str>>(mut self, name: S) -> Self {
+        self.key_style = Some(name.as_ref().to_string());
+        self
+    }
+
+    fn write_key(&self, fmt: &mut human::Formatter, key: &str) -> Result<(), Error> {
+        match &self.key_style {
+            Some(style) => fmt.write_styled(style, key)?,
+            None => fmt.write(key)?,
+        }
+        fmt.write(self.separator.as_str())
+    }
+}
+
+impl Render for KeyValue {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let key_width = self
+            .entries
+            .iter()
+            .filter_map(|entry| match entry {
+                Entry::Value(key, _) => Some(width(key) + width(&self.separator)),
+                Entry::Section(..) => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                fmt.write("\n")?;
+            }
+            match entry {
+                Entry::Value(key, value) => {
+                    self.write_key(fmt, key)?;
+                    let value = fmt.capture(value.as_ref())?.trim_end();
+                    for (i, line) in value.lines().iter().enumerate() {
+                        let padding = if i == 0 {
+                            key_width - width(key) - width(&self.separator) + 1
+                        } else {
+                            fmt.write("\n")?;
+                            key_width + 1
+                        };
+                        if !line.is_empty() {
+                            fmt.write(" ".repeat(padding))?;
+                            line.render(fmt)?;
+                        }
+                    }
+                }
+                Entry::Section(key, entries) => {
+                    self.write_key(fmt, key)?;
+                    let entries = fmt.capture(entries)?;
+                    for line in entries.lines() {
+                        fmt.write("\n")?;
+                        if !line.is_empty() {
+                            fmt.write("  ")?;
+                            line.render(fmt)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        let mut object = Map::new();
+        for entry in &self.entries {
+            let (key, value) = match entry {
+                Entry::Value(key, value) => (key, json::Formatter::capture(value.as_ref())?),
+                Entry::Section(key, entries) => (key, json::Formatter::capture(entries)?),
+            };
+            object.insert(key.clone(), value);
+        }
+        fmt.write(&Value::Object(object))
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        fmt.write_raw("<table>")?;
+        for entry in &self.entries {
+            let (key, value): (_, &dyn Render) = match entry {
+                Entry::Value(key, value) => (key, value.as_ref()),
+                Entry::Section(key, entries) => (key, entries),
+            };
+            fmt.write_raw("<tr><th>")?;
+            match &self.key_style {
+                Some(style) => fmt.write_styled(style, key)?,
+                None => fmt.write(key)?,
+            }
+            fmt.write_raw("</th><td>")?;
+            value.render_html(fmt)?;
+            fmt.write_raw("</td></tr>")?;
+        }
+        fmt.write_raw("</table>")
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        let mut rows = Vec::new();
+        for entry in &self.entries {
+            let (key, value): (_, &dyn Render) = match entry {
+                Entry::Value(key, value) => (key, value.as_ref()),
+                Entry::Section(key, entries) => (key, entries),
+            };
+            let key = match &self.key_style {
+                Some(style) => fmt.styled(style, key),
+                None => markdown::escape(key),
+            };
+            rows.push(vec![key, fmt.capture_inline(value)?]);
+        }
+
+        // Tables can't be nested, so sections in table cells are just lines
+        if fmt.inline() {
+            let separator = markdown::escape(&self.separator);
+            let lines = rows
+                .iter()
+                .map(|row| format!("{}{} {}", row[0], separator, row[1]))
+                .collect::<Vec<_>>();
+            return fmt.write_raw(lines.join("<br>"));
+        }
+        fmt.write_raw(markdown::table(&[String::new(), String::new()], &rows))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::kv;
+    use crate::components::{span, text};
+    use crate::{human, json, Error, Render};
+
+    #[test]
+    fn aligns_values() -> Result<(), Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        kv().entry("a", text("1"))
+            .entry("längere", text("2"))
+            .entry("multi", text("line\nvalue\n"))
+            .entry("empty", text(""))
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            human_output.to_string(),
+            "a:       1\n\
+             längere: 2\n\
+             multi:   line\n         value\n\
+             empty:"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_nested_sections() -> Result<(), Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        kv().separator(" =")
+            .entry("name", text("x"))
+            .section(
+                "inner",
+                kv().entry("a", text("1\n\n3")).entry("bc", text("2")),
+            )
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            human_output.to_string(),
+            "name = x\ninner =\n  a:  1\n\n      3\n  bc: 2"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn styles_keys() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let mut fmt = test_target.formatter();
+        kv().key_style("error")
+            .entry("a", span().bold(true)?.add_item("b"))
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}[0m\u{1b}[1m\u{1b}[31ma\u{1b}[0m: \u{1b}[0m\u{1b}[1mb\u{1b}[0m"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_json_objects() -> Result<(), Error> {
+        let json = json::test();
+        let mut fmt = json.formatter();
+        kv().entry("a", 1)
+            .entry("b", vec![true, false])
+            .section("c", kv().entry("d", text("e")))
+            .render_json(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            json.to_string(),
+            "{\"a\":1,\"b\":[true,false],\"c\":{\"d\":\"e\"}}"
+        );
+        Ok(())
+    }
+}
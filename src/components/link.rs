@@ -0,0 +1,255 @@
+use crate::{html, human, json, markdown, Error, Render};
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Render a link to `url`
+///
+/// Terminals that support it will show `text` as a clickable link. Everywhere
+/// else, this renders as `text (url)`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::link};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(link("the docs", "https://docs.rs/convey"))?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "the docs (https://docs.rs/convey)\n");
+/// # Ok(()) }
+/// ```
+pub fn link<T: AsRef<str>, U: AsRef<str>>(text: T, url: U) -> Link {
+    Link {
+        text: text.as_ref().to_string(),
+        url: url.as_ref().to_string(),
+    }
+}
+
+#[derive(Clone, Debug, serde_derive::Serialize)]
+pub struct Link {
+    text: String,
+    url: String,
+}
+
+impl Link {
+    fn render(fmt: &mut human::Formatter, text: &str, url: &str) -> Result<(), Error> {
+        if fmt.hyperlinks() {
+            fmt.set_hyperlink(Some(url))?;
+            fmt.write(text)?;
+            fmt.set_hyperlink(None)?;
+        } else if text == url {
+            fmt.write(text)?;
+        } else {
+            fmt.write(format!("{} ({})", text, url))?;
+        }
+        Ok(())
+    }
+
+    fn render_html(fmt: &mut html::Formatter, text: &str, url: &str) -> Result<(), Error> {
+        fmt.write_raw(format!(
+            "<a href=\"{}\">{}</a>",
+            html::escape(url),
+            html::escape(text)
+        ))
+    }
+
+    fn render_markdown(fmt: &mut markdown::Formatter, text: &str, url: &str) -> Result<(), Error> {
+        fmt.write_raw(format!(
+            "[{}]({})",
+            markdown::escape(text),
+            markdown::escape_url(url)
+        ))
+    }
+}
+
+impl Render for Link {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        Link::render(fmt, &self.text, &self.url)
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(self)?;
+        Ok(())
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        Link::render_html(fmt, &self.text, &self.url)
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        Link::render_markdown(fmt, &self.text, &self.url)
+    }
+}
+
+/// Render a path to a local file
+///
+/// On terminals that support it, the path becomes a clickable `file://` link.
+/// Everywhere else, it's just the path.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::file_link};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(file_link("src/main.rs").line(42))?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "src/main.rs:42\n");
+/// # Ok(()) }
+/// ```
+pub fn file_link<P: AsRef<Path>>(path: P) -> FileLink {
+    FileLink {
+        path: path.as_ref().to_path_buf(),
+        line: None,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FileLink {
+    path: PathBuf,
+    line: Option<usize>,
+}
+
+impl FileLink {
+    /// Point to a specific line in the file
+    ///
+    /// The line number is appended to the displayed path (`src/main.rs:42`)
+    /// and added as fragment to the URL (`file:///…/src/main.rs#42`), which
+    /// is understood by a number of terminals.
+    pub fn line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    fn text(&self) -> String {
+        match self.line {
+            Some(line) => format!("{}:{}", self.path.display(), line),
+            None => self.path.display().to_string(),
+        }
+    }
+
+    fn url(&self) -> String {
+        let path = if self.path.is_absolute() {
+            self.path.clone()
+        } else {
+            env::current_dir()
+                .map(|dir| dir.join(&self.path))
+                .unwrap_or_else(|_| self.path.clone())
+        };
+        let path = path.to_string_lossy().replace('\\', "/");
+
+        let mut url = String::from("file://");
+        if !path.starts_with('/') {
+            url.push('/');
+        }
+        for byte in path.bytes() {
+            if byte.is_ascii_alphanumeric() || b"/-._~:".contains(&byte) {
+                url.push(char::from(byte));
+            } else {
+                url.push_str(&format!("%{:02X}", byte));
+            }
+        }
+        if let Some(line) = self.line {
+            url.push_str(&format!("#{}", line));
+        }
+        url
+    }
+}
+
+impl Render for FileLink {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        if fmt.hyperlinks() {
+            Link::render(fmt, &self.text(), &self.url())
+        } else {
+            fmt.write(self.text())
+        }
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(&serde_json::json!({
+            "text": self.text(),
+            "url": self.url(),
+            "path": self.path,
+            "line": self.line,
+        }))?;
+        Ok(())
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        Link::render_html(fmt, &self.text(), &self.url())
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        Link::render_markdown(fmt, &self.text(), &self.url())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{file_link, link};
+    use crate::{human, json, Error, Render};
+
+    #[test]
+    fn renders_links_as_text_by_default() -> Result<(), Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        link("docs", "https://docs.rs").render_for_humans(&mut fmt)?;
+        fmt.write(" ")?;
+        link("https://docs.rs", "https://docs.rs").render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            human_output.to_string(),
+            "docs (https://docs.rs) https://docs.rs"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_hyperlinks() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let out = crate::new().add_target(test_target.target().with_hyperlinks(true)?)?;
+
+        out.print(link("docs", "https://docs.rs"))?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}]8;;https://docs.rs\u{1b}\\docs\u{1b}]8;;\u{1b}\\\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_file_links() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let out = crate::new().add_target(test_target.target().with_hyperlinks(true)?)?;
+
+        out.print(file_link("/tmp/some file.rs").line(3))?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}]8;;file:///tmp/some%20file.rs#3\u{1b}\\/tmp/some file.rs:3\u{1b}]8;;\u{1b}\\\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_links_as_json() -> Result<(), Error> {
+        let json = json::test();
+        let mut fmt = json.formatter();
+        link("docs", "https://docs.rs").render_json(&mut fmt)?;
+        file_link("/tmp/foo.rs").line(3).render_json(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            json.to_string(),
+            "{\"text\":\"docs\",\"url\":\"https://docs.rs\"}\
+             {\"line\":3,\"path\":\"/tmp/foo.rs\",\"text\":\"/tmp/foo.rs:3\",\"url\":\"file:///tmp/foo.rs#3\"}"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+use crate::{html, human, json, markdown, Error, Render};
+
+/// Render a bulleted list
+///
+/// Items that span multiple lines, or that are wrapped to fit the target's
+/// width, are indented to line up with the first line. In JSON, this is an
+/// array.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::{list, text}};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target().with_width(20)?)?;
+/// out.print(
+///     list()
+///         .item(text("short"))
+///         .item(text("a longer item that needs to wrap"))
+///         .nested(list().item(text("nested"))),
+/// )?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "\
+/// # - short
+/// # - a longer item that
+/// #   needs to wrap
+/// #   - nested
+/// # ");
+/// # Ok(()) }
+/// ```
+pub fn list() -> List {
+    List {
+        entries: Vec::new(),
+        ordered: false,
+        start: 1,
+    }
+}
+
+/// Render a numbered list
+///
+/// Same as [`list`], but items are numbered starting at 1.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::{ordered_list, text}};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(ordered_list().item(text("first")).item(text("second")))?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "1. first\n2. second\n");
+/// # Ok(()) }
+/// ```
+pub fn ordered_list() -> List {
+    List {
+        entries: Vec::new(),
+        ordered: true,
+        start: 1,
+    }
+}
+
+pub struct List {
+    entries: Vec<Entry>,
+    ordered: bool,
+    start: usize,
+}
+
+enum Entry {
+    Item(Box<dyn Render>),
+    Nested(List),
+}
+
+impl List {
+    /// Add an item
+    pub fn item<T: Render + 'static>(mut self, item: T) -> Self {
+        self.entries.push(Entry::Item(Box::new(item)));
+        self
+    }
+
+    /// Start numbering at `number` (default: 1)
+    pub fn start(mut self, number: usize) -> Self {
+        self.start = number;
+        self
+    }
+
+    /// Add a list that belongs to the previous item
+    ///
+    /// It is indented to line up with the previous item's text. In JSON, it
+    /// is a nested array.
+    pub fn nested(mut self, list: List) -> Self {
+        self.entries.push(Entry::Nested(list));
+        self
+    }
+}
+
+impl Render for List {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let count = self
+            .entries
+            .iter()
+            .filter(|entry| matches!(entry, Entry::Item(..)))
+            .count();
+        let digits = (self.start + count).saturating_sub(1).to_string().len();
+        let indent = if self.ordered { digits + 2 } else { 2 };
+
+        let mut number = self.start;
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                fmt.write("\n")?;
+            }
+            match entry {
+                Entry::Item(item) => {
+                    let marker = if self.ordered {
+                        format!("{:>width$}. ", number, width = digits)
+                    } else {
+                        String::from("- ")
+                    };
+                    write_item(fmt, &marker, indent, item.as_ref())?;
+                    number += 1;
+                }
+                Entry::Nested(list) => write_item(fmt, &" ".repeat(indent), indent, list)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        let values = self
+            .entries
+            .iter()
+            .map(|entry| match entry {
+                Entry::Item(item) => json::Formatter::capture(item.as_ref()),
+                Entry::Nested(list) => json::Formatter::capture(list),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        fmt.write(&values)
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        let tag = if self.ordered { "ol" } else { "ul" };
+        if self.ordered && self.start != 1 {
+            fmt.write_raw(format!("<ol start=\"{}\">", self.start))?;
+        } else {
+            fmt.write_raw(format!("<{}>", tag))?;
+        }
+
+        // Nested lists belong into the item before them
+        let mut open = false;
+        for entry in &self.entries {
+            match entry {
+                Entry::Item(item) => {
+                    if open {
+                        fmt.write_raw("</li>")?;
+                    }
+                    fmt.write_raw("<li>")?;
+                    item.render_html(fmt)?;
+                    open = true;
+                }
+                Entry::Nested(list) => {
+                    if !open {
+                        fmt.write_raw("<li>")?;
+                        open = true;
+                    }
+                    list.render_html(fmt)?;
+                }
+            }
+        }
+        if open {
+            fmt.write_raw("</li>")?;
+        }
+        fmt.write_raw(format!("</{}>", tag))
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        let mut lines = Vec::new();
+        let mut number = self.start;
+        let mut indent = 0;
+        for entry in &self.entries {
+            match entry {
+                Entry::Item(item) => {
+                    let marker = if self.ordered {
+                        format!("{}. ", number)
+                    } else {
+                        String::from("- ")
+                    };
+                    lines.push(markdown::list_item(&marker, &fmt.capture(item.as_ref())?));
+                    indent = marker.len();
+                    number += 1;
+                }
+                Entry::Nested(list) => {
+                    // Only lists starting at 1 can interrupt the text of an item
+                    if list.ordered && list.start != 1 {
+                        lines.push(String::new());
+                    }
+                    let marker = " ".repeat(indent);
+                    lines.push(markdown::list_item(&marker, &fmt.capture(list)?));
+                }
+            }
+        }
+        fmt.write_raw(lines.join("\n"))
+    }
+}
+
+/// Write `marker` followed by `item`, with following lines indented by
+/// `indent` columns
+///
+/// The item is wrapped to fit into the width that is left.
+pub(crate) fn write_item(
+    fmt: &mut human::Formatter,
+    marker: &str,
+    indent: usize,
+    item: &dyn Render,
+) -> Result<(), Error> {
+    let width = fmt.width().map(|width| width.saturating_sub(indent).max(1));
+    let mut inner = fmt.clone();
+    inner.set_width(width);
+    let mut lines = inner.capture(item)?.trim_end();
+    if let Some(width) = width {
+        lines = lines.wrap(width);
+    }
+
+    fmt.write(marker)?;
+    for (i, line) in lines.lines().iter().enumerate() {
+        if i > 0 {
+            fmt.write("\n")?;
+            if !line.is_empty() {
+                fmt.write(" ".repeat(indent))?;
+            }
+        }
+        line.render(fmt)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{list, ordered_list};
+    use crate::components::text;
+    use crate::{human, json, Error, Render};
+
+    #[test]
+    fn indents_multiple_lines() -> Result<(), Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        list()
+            .item(text("a\nb"))
+            .nested(ordered_list().item(text("c\n\nd")))
+            .item(text("e"))
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(human_output.to_string(), "- a\n  b\n  1. c\n\n     d\n- e");
+        Ok(())
+    }
+
+    #[test]
+    fn aligns_numbers() -> Result<(), Error> {
+        let test_target = human::test();
+        let out = crate::new().add_target(test_target.target().with_width(12)?)?;
+        let items = (1..=10).fold(ordered_list(), |list, i| {
+            list.item(text(if i == 10 { "wrapped item" } else { "x" }))
+        });
+        out.print(items)?;
+        out.flush()?;
+        assert_eq!(
+            test_target.to_string(),
+            " 1. x\n 2. x\n 3. x\n 4. x\n 5. x\n 6. x\n 7. x\n 8. x\n 9. x\n\
+             10. wrapped\n    item\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn starts_at_other_numbers() -> Result<(), Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        ordered_list()
+            .start(9)
+            .item(text("a"))
+            .item(text("b"))
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(human_output.to_string(), " 9. a\n10. b");
+        Ok(())
+    }
+
+    #[test]
+    fn renders_json_arrays() -> Result<(), Error> {
+        let json = json::test();
+        let mut fmt = json.formatter();
+        list()
+            .item(text("a"))
+            .nested(list().item(1).item(2))
+            .render_json(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(json.to_string(), "[\"a\",[1,2]]");
+        Ok(())
+    }
+}
@@ -0,0 +1,660 @@
+use super::heading::{heading, rule};
+use super::list::{list, ordered_list};
+use crate::theme::Style;
+use crate::{html, human, json, layout, markdown as md, Error, Render};
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+
+/// Render Markdown
+///
+/// For humans, this renders headings, emphasis, inline code, lists, block
+/// quotes, links, and code blocks using the target's theme, and wraps
+/// paragraphs to fit the target's width. In HTML, the blocks become the
+/// matching elements, like `<h1>`, `<ul>`, or `<code>`. In JSON, this is the
+/// Markdown source as a string, and Markdown targets get the source as it is.
+///
+/// This is only available with the `markdown` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::markdown};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target().with_width(30)?)?;
+/// out.print(markdown("\
+/// Usage
+/// -----
+///
+/// Run `convey` with **one** of these options:
+///
+/// 1. `--fast`, which is fast
+/// 2. `--slow`, which is not as fast but a lot more thorough
+/// "))?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "\
+/// # Usage
+/// # -----
+/// #
+/// # Run convey with one of these
+/// # options:
+/// #
+/// # 1. --fast, which is fast
+/// # 2. --slow, which is not as
+/// #    fast but a lot more
+/// #    thorough
+/// # ");
+/// # Ok(()) }
+/// ```
+pub fn markdown<T: AsRef<str>>(source: T) -> Markdown {
+    Markdown {
+        source: source.as_ref().to_string(),
+    }
+}
+
+pub struct Markdown {
+    source: String,
+}
+
+#[derive(Clone, Debug)]
+enum Block {
+    Paragraph(Inlines),
+    Heading(u8, Inlines),
+    Quote(Blocks),
+    /// Start number if ordered, whether it is tight, and the items
+    List(Option<u64>, bool, Vec<Blocks>),
+    Code(Option<String>, String),
+    Rule,
+}
+
+/// A sequence of blocks, separated by blank lines (or just line breaks in
+/// tight lists)
+#[derive(Clone, Debug)]
+struct Blocks {
+    blocks: Vec<Block>,
+    tight: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Inlines(Vec<Inline>);
+
+#[derive(Clone, Debug)]
+enum Inline {
+    Text(String, Attributes),
+    LinkStart(String),
+    LinkEnd(String),
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Attributes {
+    emphasis: bool,
+    strong: bool,
+    strikethrough: bool,
+    code: bool,
+    link: bool,
+}
+
+/// A list that is being parsed
+struct List {
+    start: Option<u64>,
+    items: Vec<Blocks>,
+    /// Number of containers when inside one of the items
+    depth: usize,
+    /// Whether items contain paragraphs, which are separated by blank lines
+    loose: bool,
+}
+
+fn parse(source: &str) -> Blocks {
+    let mut containers = vec![Vec::new()];
+    let mut lists: Vec<List> = Vec::new();
+    let mut inlines = Vec::new();
+    let mut attributes = Attributes::default();
+    let mut code: Option<(Option<String>, String)> = None;
+
+    // Tight list items contain text without a paragraph around it
+    fn flush(containers: &mut [Vec<Block>], inlines: &mut Vec<Inline>) {
+        if !inlines.is_empty() {
+            let paragraph = Block::Paragraph(Inlines(std::mem::take(inlines)));
+            if let Some(container) = containers.last_mut() {
+                container.push(paragraph);
+            }
+        }
+    }
+
+    for event in Parser::new_ext(source, Options::ENABLE_STRIKETHROUGH) {
+        let block = match event {
+            Event::Start(Tag::Paragraph) => {
+                flush(&mut containers, &mut inlines);
+                // Only items of loose lists contain paragraphs
+                if let Some(list) = lists.last_mut() {
+                    if list.depth == containers.len() {
+                        list.loose = true;
+                    }
+                }
+                None
+            }
+            Event::Start(Tag::Heading { .. }) | Event::Start(Tag::Item) => {
+                flush(&mut containers, &mut inlines);
+                if let Event::Start(Tag::Item) = event {
+                    containers.push(Vec::new());
+                }
+                None
+            }
+            Event::End(TagEnd::Paragraph) => Some(Block::Paragraph(Inlines(inlines.split_off(0)))),
+            Event::End(TagEnd::Heading(level)) => {
+                Some(Block::Heading(level as u8, Inlines(inlines.split_off(0))))
+            }
+            Event::Start(Tag::BlockQuote(_)) => {
+                flush(&mut containers, &mut inlines);
+                containers.push(Vec::new());
+                None
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                flush(&mut containers, &mut inlines);
+                let blocks = containers.pop().unwrap_or_default();
+                Some(Block::Quote(Blocks {
+                    blocks,
+                    tight: false,
+                }))
+            }
+            Event::Start(Tag::List(start)) => {
+                flush(&mut containers, &mut inlines);
+                lists.push(List {
+                    start,
+                    items: Vec::new(),
+                    depth: containers.len() + 1,
+                    loose: false,
+                });
+                None
+            }
+            Event::End(TagEnd::List(_)) => lists.pop().map(|list| {
+                let tight = !list.loose;
+                let items = list
+                    .items
+                    .into_iter()
+                    .map(|blocks| Blocks { tight, ..blocks })
+                    .collect();
+                Block::List(list.start, tight, items)
+            }),
+            Event::End(TagEnd::Item) => {
+                flush(&mut containers, &mut inlines);
+                let blocks = containers.pop().unwrap_or_default();
+                if let Some(list) = lists.last_mut() {
+                    list.items.push(Blocks {
+                        blocks,
+                        tight: true,
+                    });
+                }
+                None
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                flush(&mut containers, &mut inlines);
+                let language = match kind {
+                    CodeBlockKind::Fenced(language) if !language.is_empty() => {
+                        Some(language.to_string())
+                    }
+                    _ => None,
+                };
+                code = Some((language, String::new()));
+                None
+            }
+            Event::End(TagEnd::CodeBlock) => code
+                .take()
+                .map(|(language, source)| Block::Code(language, source)),
+            Event::Rule => {
+                flush(&mut containers, &mut inlines);
+                Some(Block::Rule)
+            }
+            Event::Start(Tag::Emphasis) | Event::End(TagEnd::Emphasis) => {
+                attributes.emphasis = matches!(event, Event::Start(..));
+                None
+            }
+            Event::Start(Tag::Strong) | Event::End(TagEnd::Strong) => {
+                attributes.strong = matches!(event, Event::Start(..));
+                None
+            }
+            Event::Start(Tag::Strikethrough) | Event::End(TagEnd::Strikethrough) => {
+                attributes.strikethrough = matches!(event, Event::Start(..));
+                None
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                attributes.link = true;
+                inlines.push(Inline::LinkStart(dest_url.to_string()));
+                None
+            }
+            Event::End(TagEnd::Link) => {
+                attributes.link = false;
+                let url = inlines.iter().rev().find_map(|inline| match inline {
+                    Inline::LinkStart(url) => Some(url.clone()),
+                    _ => None,
+                });
+                inlines.push(Inline::LinkEnd(url.unwrap_or_default()));
+                None
+            }
+            Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                match &mut code {
+                    Some((_, source)) => source.push_str(&text),
+                    None => inlines.push(Inline::Text(text.to_string(), attributes)),
+                }
+                None
+            }
+            Event::Code(text) => {
+                let attributes = Attributes {
+                    code: true,
+                    ..attributes
+                };
+                inlines.push(Inline::Text(text.to_string(), attributes));
+                None
+            }
+            Event::SoftBreak => {
+                inlines.push(Inline::Text(String::from(" "), attributes));
+                None
+            }
+            Event::HardBreak => {
+                inlines.push(Inline::Text(String::from("\n"), attributes));
+                None
+            }
+            Event::TaskListMarker(done) => {
+                let marker = if done { "[x] " } else { "[ ] " };
+                inlines.push(Inline::Text(String::from(marker), attributes));
+                None
+            }
+            _ => None,
+        };
+
+        if let (Some(block), Some(container)) = (block, containers.last_mut()) {
+            container.push(block);
+        }
+    }
+
+    flush(&mut containers, &mut inlines);
+    Blocks {
+        blocks: containers.into_iter().next().unwrap_or_default(),
+        tight: false,
+    }
+}
+
+impl Attributes {
+    fn style(self, fmt: &human::Formatter) -> Style {
+        let base = match (self.code, fmt.theme().get("code")) {
+            (true, Some(style)) => style.clone(),
+            _ => Style::new(),
+        };
+        let mut style = Style::new();
+        if self.emphasis {
+            style = style.italic(true);
+        }
+        if self.strong {
+            style = style.bold(true);
+        }
+        if self.strikethrough {
+            style = style.strikethrough(true);
+        }
+        if self.link {
+            style = style.underline(true);
+        }
+        base.merge(&style)
+    }
+}
+
+impl Render for Inlines {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        for inline in &self.0 {
+            match inline {
+                Inline::Text(text, attributes) => {
+                    let style = attributes.style(fmt);
+                    if style == Style::default() {
+                        fmt.write(text.as_str())?;
+                    } else {
+                        fmt.set_style(&style)?;
+                        fmt.write(text.as_str())?;
+                        fmt.reset()?;
+                    }
+                }
+                Inline::LinkStart(url) => fmt.set_hyperlink(Some(url))?,
+                Inline::LinkEnd(_) if fmt.hyperlinks() => fmt.set_hyperlink(None)?,
+                Inline::LinkEnd(url) => fmt.write_styled("dim", format!(" ({})", url))?,
+            }
+        }
+        Ok(())
+    }
+
+    fn render_json(&self, _fmt: &mut json::Formatter) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        for inline in &self.0 {
+            match inline {
+                Inline::Text(text, attributes) => {
+                    let tags = [
+                        (attributes.strikethrough, "del"),
+                        (attributes.strong, "strong"),
+                        (attributes.emphasis, "em"),
+                        (attributes.code, "code"),
+                    ];
+                    let tags = tags.iter().filter(|(set, _)| *set).map(|(_, tag)| tag);
+                    for tag in tags.clone() {
+                        fmt.write_raw(format!("<{}>", tag))?;
+                    }
+                    let text = html::escape(text).replace('\n', "<br>");
+                    fmt.write_raw(text)?;
+                    for tag in tags.rev() {
+                        fmt.write_raw(format!("</{}>", tag))?;
+                    }
+                }
+                Inline::LinkStart(url) => {
+                    fmt.write_raw(format!("<a href=\"{}\">", html::escape(url)))?
+                }
+                Inline::LinkEnd(_) => fmt.write_raw("</a>")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write lines of `item`, wrapped to fit and each starting with `prefix`
+fn write_prefixed(
+    fmt: &mut human::Formatter,
+    prefix: &str,
+    style: &str,
+    item: &dyn Render,
+) -> Result<(), Error> {
+    let width = fmt
+        .width()
+        .map(|width| width.saturating_sub(layout::width(prefix)).max(1));
+    let mut inner = fmt.clone();
+    inner.set_width(width);
+    let mut lines = inner.capture(item)?.trim_end();
+    if let Some(width) = width {
+        lines = lines.wrap(width);
+    }
+
+    for (i, line) in lines.lines().iter().enumerate() {
+        if i > 0 {
+            fmt.write("\n")?;
+        }
+        fmt.write_styled(style, prefix)?;
+        line.render(fmt)?;
+    }
+    Ok(())
+}
+
+impl Block {
+    fn render(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        match self {
+            Block::Paragraph(inlines) => write_prefixed(fmt, "", "", inlines),
+            Block::Heading(level, inlines) => {
+                heading(*level, inlines.clone()).render_for_humans(fmt)
+            }
+            Block::Quote(blocks) => {
+                let prefix = format!("{} ", fmt.glyphs().vertical);
+                write_prefixed(fmt, &prefix, "dim", blocks)
+            }
+            Block::List(start, true, items) => {
+                let list = match start {
+                    Some(start) => ordered_list().start(*start as usize),
+                    None => list(),
+                };
+                items
+                    .iter()
+                    .fold(list, |list, item| list.item(item.clone()))
+                    .render_for_humans(fmt)
+            }
+            // Items of loose lists are separated by blank lines, like the
+            // paragraphs in them
+            Block::List(start, false, items) => {
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        fmt.write("\n\n")?;
+                    }
+                    let list = match start {
+                        Some(start) => ordered_list().start(*start as usize + i),
+                        None => list(),
+                    };
+                    list.item(item.clone()).render_for_humans(fmt)?;
+                }
+                Ok(())
+            }
+            Block::Code(language, source) => {
+                let source = source.trim_end_matches('\n');
+                #[cfg(feature = "syntax-highlighting")]
+                {
+                    if let Some(language) = language {
+                        let code = super::code::code(source, language);
+                        return write_prefixed(fmt, "    ", "", &code);
+                    }
+                }
+                let _ = language;
+                for (i, line) in source.lines().enumerate() {
+                    if i > 0 {
+                        fmt.write("\n")?;
+                    }
+                    fmt.write("    ")?;
+                    fmt.write_styled("code", line)?;
+                }
+                Ok(())
+            }
+            Block::Rule => rule().render_for_humans(fmt),
+        }
+    }
+
+    /// Write the block as HTML, without `<p>` around the paragraphs of tight
+    /// list items
+    fn render_html(&self, fmt: &mut html::Formatter, tight: bool) -> Result<(), Error> {
+        match self {
+            Block::Paragraph(inlines) if tight => inlines.render_html(fmt),
+            Block::Paragraph(inlines) => {
+                fmt.write_raw("<p>")?;
+                inlines.render_html(fmt)?;
+                fmt.write_raw("</p>")
+            }
+            Block::Heading(level, inlines) => {
+                let level = (*level).clamp(1, 6);
+                fmt.write_raw(format!("<h{}>", level))?;
+                inlines.render_html(fmt)?;
+                fmt.write_raw(format!("</h{}>", level))
+            }
+            Block::Quote(blocks) => {
+                fmt.write_raw("<blockquote>")?;
+                blocks.render_html(fmt)?;
+                fmt.write_raw("</blockquote>")
+            }
+            Block::List(start, _, items) => {
+                let tag = if start.is_some() { "ol" } else { "ul" };
+                match start {
+                    Some(start) if *start != 1 => {
+                        fmt.write_raw(format!("<ol start=\"{}\">", start))?
+                    }
+                    _ => fmt.write_raw(format!("<{}>", tag))?,
+                }
+                for item in items {
+                    fmt.write_raw("<li>")?;
+                    item.render_html(fmt)?;
+                    fmt.write_raw("</li>")?;
+                }
+                fmt.write_raw(format!("</{}>", tag))
+            }
+            Block::Code(language, source) => {
+                let class = match language {
+                    Some(language) => format!(" class=\"language-{}\"", html::escape(language)),
+                    None => String::new(),
+                };
+                fmt.write_raw(format!(
+                    "<pre><code{}>{}</code></pre>",
+                    class,
+                    html::escape(source.trim_end_matches('\n'))
+                ))
+            }
+            Block::Rule => fmt.write_raw("<hr>"),
+        }
+    }
+}
+
+impl Render for Blocks {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let separator = if self.tight { "\n" } else { "\n\n" };
+        for (i, block) in self.blocks.iter().enumerate() {
+            if i > 0 {
+                fmt.write(separator)?;
+            }
+            block.render(fmt)?;
+        }
+        Ok(())
+    }
+
+    fn render_json(&self, _fmt: &mut json::Formatter) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        for block in &self.blocks {
+            block.render_html(fmt, self.tight)?;
+        }
+        Ok(())
+    }
+}
+
+impl Render for Markdown {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        parse(&self.source).render_for_humans(fmt)
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(&self.source)
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        parse(&self.source).render_html(fmt)
+    }
+
+    fn render_markdown(&self, fmt: &mut md::Formatter) -> Result<(), Error> {
+        if fmt.inline() {
+            return fmt.write_human(self);
+        }
+        fmt.write_raw(self.source.trim_end())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::markdown;
+    use crate::{html, human, json, markdown as md, Error, Render};
+
+    fn render(source: &str) -> Result<String, Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        markdown(source).render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        Ok(human_output.to_string())
+    }
+
+    #[test]
+    fn renders_blocks() -> Result<(), Error> {
+        assert_eq!(
+            render("# Title\n\n> quoted\n> text\n\n- a\n- b\n  - c\n\n---\n\n```\nx\n  y\n```\n")?,
+            format!(
+                "Title\n=====\n\n│ quoted text\n\n- a\n- b\n  - c\n\n{}\n\n    x\n      y",
+                "─".repeat(80)
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn writes_the_source_to_markdown_targets() -> Result<(), Error> {
+        let test_target = md::test();
+        let out = crate::new().add_target(test_target.target())?;
+        out.print(markdown("# Title\n\nSome **bold** and `code`.\n"))?;
+        out.print(markdown("- a\n- b"))?;
+        out.flush()?;
+        assert_eq!(
+            test_target.to_string(),
+            "# Title\n\nSome **bold** and `code`.\n\n- a\n- b\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn maps_blocks_to_html_elements() -> Result<(), Error> {
+        let test_target = html::test();
+        let out = crate::new().add_target(test_target.target())?;
+        out.print(markdown(
+            "# Title\n\nSome **bold**, *italic* <b>\nand [a link](https://a.b?c&d).\n\n\
+             > `code`\n\n3. x\n4. y\n\n- z\n\n  more\n\n```rust\nlet a = 1;\n```\n\n---\n",
+        ))?;
+        out.flush()?;
+        assert_eq!(
+            test_target.to_string(),
+            "<div><h1>Title</h1>\
+             <p>Some <strong>bold</strong>, <em>italic</em> &lt;b&gt; and \
+             <a href=\"https://a.b?c&amp;d\">a link</a>.</p>\
+             <blockquote><p><code>code</code></p></blockquote>\
+             <ol start=\"3\"><li>x</li><li>y</li></ol>\
+             <ul><li><p>z</p><p>more</p></li></ul>\
+             <pre><code class=\"language-rust\">let a = 1;</code></pre>\
+             <hr></div>\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_loose_lists_loose() -> Result<(), Error> {
+        assert_eq!(
+            render("1. a\n\n2. b\n   - c\n   - d\n")?,
+            "1. a\n\n2. b\n\n   - c\n   - d"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn wraps_quotes_to_the_width() -> Result<(), Error> {
+        let test_target = human::test();
+        let out = crate::new().add_target(test_target.target().with_width(9)?)?;
+        out.print(markdown("> abc def"))?;
+        out.flush()?;
+        assert_eq!(test_target.to_string(), "│ abc def\n");
+        Ok(())
+    }
+
+    #[test]
+    fn renders_links() -> Result<(), Error> {
+        assert_eq!(
+            render("See [the docs](https://docs.rs).")?,
+            "See the docs (https://docs.rs)."
+        );
+
+        let test_target = human::test_with_color();
+        let out = crate::new().add_target(test_target.target().with_hyperlinks(true)?)?;
+        out.print(markdown("[x](https://a.b)"))?;
+        out.flush()?;
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}]8;;https://a.b\u{1b}\\\u{1b}[0m\u{1b}[4mx\u{1b}[0m\u{1b}]8;;\u{1b}\\\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn styles_inline_text() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let mut fmt = test_target.formatter();
+        markdown("*a* **b** ~~c~~").render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}[0m\u{1b}[3ma\u{1b}[0m \u{1b}[0m\u{1b}[1mb\u{1b}[0m \u{1b}[0m\u{1b}[9mc\u{1b}[0m"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_source_as_json() -> Result<(), Error> {
+        let json = json::test();
+        let mut fmt = json.formatter();
+        markdown("*hi*").render_json(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(json.to_string(), "\"*hi*\"");
+        Ok(())
+    }
+}
@@ -1,8 +1,40 @@
 //! Components to help build your output from.
 
+pub use self::adapters::{debug, display, human_json};
+#[cfg(feature = "syntax-highlighting")]
+pub use self::code::code;
+pub use self::columns::{columns, ColumnWidth};
+pub use self::diagnostic::{diagnostic, Severity};
+pub use self::diff::diff;
+pub use self::heading::{heading, rule, section};
+pub use self::kv::kv;
+pub use self::link::{file_link, link};
+pub use self::list::{list, ordered_list};
+#[cfg(feature = "markdown")]
+pub use self::markdown::markdown;
+pub use self::panel::{panel, Border};
 pub use self::span::span;
+pub use self::status::{status, Status};
 pub use self::text::newline;
 pub use self::text::text;
+pub use self::tree::tree;
+pub use self::units::{bytes, count, duration, relative_time};
 
+mod adapters;
+#[cfg(feature = "syntax-highlighting")]
+mod code;
+mod columns;
+mod diagnostic;
+mod diff;
+pub(crate) mod heading;
+mod kv;
+mod link;
+pub(crate) mod list;
+#[cfg(feature = "markdown")]
+mod markdown;
+mod panel;
 mod span;
+mod status;
 mod text;
+mod tree;
+mod units;
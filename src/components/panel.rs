@@ -0,0 +1,312 @@
+use crate::color::ToColor;
+use crate::glyphs::UnicodeSupport;
+use crate::layout::{split_at_width, width};
+use crate::theme::Style;
+use crate::{human, json, Error, Render};
+use serde_json::json;
+
+/// Render something in a box
+///
+/// The content is wrapped to fit into the panel, which spans the whole width
+/// of the target (or fits the content if the width is unknown). In JSON, the
+/// panel is transparent and only its content is rendered, unless it has a
+/// [`kind`](Panel::kind).
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::{panel, text, Border}};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target().with_width(24)?)?;
+/// out.print(
+///     panel(text("Your config file uses deprecated options."))
+///         .title("Warning")
+///         .kind("warning")
+///         .border(Border::Rounded),
+/// )?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "\
+/// # ╭─ Warning ────────────╮
+/// # │ Your config file     │
+/// # │ uses deprecated      │
+/// # │ options.             │
+/// # ╰──────────────────────╯
+/// # ");
+/// # Ok(()) }
+/// ```
+pub fn panel<T: Render + 'static>(content: T) -> Panel {
+    Panel {
+        content: Box::new(content),
+        title: None,
+        kind: None,
+        border: None,
+        padding: 1,
+        style: None,
+    }
+}
+
+pub struct Panel {
+    content: Box<dyn Render>,
+    title: Option<String>,
+    kind: Option<String>,
+    border: Option<Border>,
+    padding: usize,
+    style: Option<Style>,
+}
+
+/// Characters to draw the border of a [`panel`] with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Border {
+    /// `┌─┐`
+    Single,
+    /// `╔═╗`
+    Double,
+    /// `╭─╮`
+    Rounded,
+    /// `+-+`
+    Ascii,
+}
+
+impl Border {
+    /// The corners, clockwise from the top left, and the horizontal and
+    /// vertical lines, in the order of the shared glyph table
+    fn glyphs(self) -> ([&'static str; 4], &'static str, &'static str) {
+        match self {
+            Border::Single => (["┌", "┐", "┘", "└"], "─", "│"),
+            Border::Double => (["╔", "╗", "╝", "╚"], "═", "║"),
+            Border::Rounded => (["╭", "╮", "╯", "╰"], "─", "│"),
+            Border::Ascii => (["+", "+", "+", "+"], "-", "|"),
+        }
+    }
+}
+
+impl Panel {
+    /// Show a title in the top border
+    pub fn title<T: AsRef<str>>(mut self, title: T) -> Self {
+        self.title = Some(title.as_ref().to_string());
+        self
+    }
+
+    /// Set what kind of panel this is, e.g. `"warning"`
+    ///
+    /// Unless a color is set explicitly, the border uses the theme's style of
+    /// that name. In JSON, the content is wrapped in an object like
+    /// `{"kind": "warning", "content": ...}`.
+    pub fn kind<T: AsRef<str>>(mut self, kind: T) -> Self {
+        self.kind = Some(kind.as_ref().to_string());
+        self
+    }
+
+    /// Set the characters the border is drawn with (default: the target's
+    /// glyphs)
+    ///
+    /// On targets that don't support unicode, ASCII is used regardless.
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    /// Set the number of spaces between border and content (default: 1)
+    pub fn padding(mut self, padding: usize) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the color of the border
+    ///
+    /// See the [`color`](../color/index.html) module for supported values.
+    pub fn color<C: ToColor>(mut self, color: C) -> Result<Self, Error> {
+        self.style = Some(Style::new().fg(color.to_color()?));
+        Ok(self)
+    }
+
+    fn write_border(&self, fmt: &mut human::Formatter, border: &str) -> Result<(), Error> {
+        let style = match (&self.style, &self.kind) {
+            (Some(style), _) => Some(style.clone()),
+            (None, Some(kind)) => fmt.theme().get(kind).cloned(),
+            (None, None) => None,
+        };
+        match style {
+            Some(style) => {
+                fmt.set_style(&style)?;
+                fmt.write(border)?;
+                fmt.reset()
+            }
+            None => fmt.write(border),
+        }
+    }
+}
+
+impl Render for Panel {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let (corners, horizontal, vertical) = match (self.border, fmt.unicode_support()) {
+            (Some(border), UnicodeSupport::Unicode) | (Some(border), UnicodeSupport::Emoji) => {
+                border.glyphs()
+            }
+            _ => {
+                let glyphs = fmt.glyphs();
+                (glyphs.corners, glyphs.horizontal, glyphs.vertical)
+            }
+        };
+        let [top_left, top_right, bottom_right, bottom_left] = corners;
+        let frame = 2 * (width(vertical) + self.padding);
+
+        let mut inner = fmt.clone();
+        inner.set_width(fmt.width().map(|width| width.saturating_sub(frame).max(1)));
+        let mut content = inner.capture(self.content.as_ref())?.trim_end();
+        let content_width = match inner.width() {
+            Some(width) => {
+                content = content.wrap(width);
+                width
+            }
+            None => {
+                let title_width = self.title.as_ref().map_or(0, |title| width(title) + 4);
+                content
+                    .width()
+                    .max(title_width.saturating_sub(2 * self.padding))
+            }
+        };
+        let line = |columns: usize| horizontal.repeat(columns);
+
+        // Top border, with the title if there is one
+        let top_width = content_width + 2 * self.padding;
+        self.write_border(fmt, top_left)?;
+        // Wide characters that don't fit are left out of the title
+        let title = self
+            .title
+            .as_ref()
+            .map(|title| split_at_width(title, top_width.saturating_sub(3)).0);
+        match title {
+            Some(title) if !title.is_empty() => {
+                self.write_border(fmt, &format!("{} ", line(1)))?;
+                fmt.write_styled("heading", title)?;
+                let rest = top_width.saturating_sub(width(title) + 3);
+                self.write_border(fmt, &format!(" {}", line(rest)))?;
+            }
+            _ => self.write_border(fmt, &line(top_width))?,
+        }
+        self.write_border(fmt, top_right)?;
+
+        let padding = " ".repeat(self.padding);
+        for line in content.lines() {
+            fmt.write("\n")?;
+            self.write_border(fmt, vertical)?;
+            fmt.write(padding.as_str())?;
+            line.render(fmt)?;
+            let fill = content_width.saturating_sub(line.width());
+            fmt.write(format!("{}{}", " ".repeat(fill), padding))?;
+            self.write_border(fmt, vertical)?;
+        }
+
+        fmt.write("\n")?;
+        self.write_border(fmt, bottom_left)?;
+        self.write_border(fmt, &line(top_width))?;
+        self.write_border(fmt, bottom_right)
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        match &self.kind {
+            Some(kind) => fmt.write(&json!({
+                "kind": kind,
+                "content": json::Formatter::capture(self.content.as_ref())?,
+            })),
+            None => self.content.render_json(fmt),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{panel, Border};
+    use crate::components::text;
+    use crate::glyphs::UnicodeSupport;
+    use crate::{human, json, Error, Render};
+
+    #[test]
+    fn fits_content_without_width() -> Result<(), Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        panel(text("a\nbcd"))
+            .border(Border::Ascii)
+            .padding(0)
+            .title("Title")
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            human_output.to_string(),
+            "+- Title -+\n|a        |\n|bcd      |\n+---------+"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn leaves_out_wide_title_characters_that_dont_fit() -> Result<(), Error> {
+        let test_target = human::test();
+        let out = crate::new().add_target(test_target.target().with_width(6)?)?;
+        out.print(panel(text("ab")).border(Border::Ascii).title("日本語"))?;
+        out.flush()?;
+        assert_eq!(test_target.to_string(), "+----+\n| ab |\n+----+\n");
+        Ok(())
+    }
+
+    #[test]
+    fn draws_default_borders_with_the_targets_glyphs() -> Result<(), Error> {
+        let test_target = human::test();
+        let mut fmt = test_target.formatter();
+        panel(text("x")).render_for_humans(&mut fmt)?;
+        fmt.write("\n")?;
+        fmt.set_unicode_support(UnicodeSupport::Ascii);
+        panel(text("x"))
+            .border(Border::Rounded)
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            test_target.to_string(),
+            "┌───┐\n│ x │\n└───┘\n+---+\n| x |\n+---+"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn colors_borders() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let mut fmt = test_target.formatter();
+        panel(text("x"))
+            .border(Border::Double)
+            .color("red")?
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        let red = |s: &str| format!("\u{1b}[0m\u{1b}[31m{}\u{1b}[0m", s);
+        assert_eq!(
+            test_target.to_string(),
+            format!(
+                "{}{}{}\n{} x {}\n{}{}{}",
+                red("╔"),
+                red("═══"),
+                red("╗"),
+                red("║"),
+                red("║"),
+                red("╚"),
+                red("═══"),
+                red("╝")
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn is_transparent_in_json() -> Result<(), Error> {
+        let json = json::test();
+        let mut fmt = json.formatter();
+        panel(text("a")).render_json(&mut fmt)?;
+        panel(text("b")).kind("note").render_json(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            json.to_string(),
+            "\"a\"{\"content\":\"b\",\"kind\":\"note\"}"
+        );
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
-use crate::{human, json, Error, Render};
-use termcolor::ColorSpec;
+use crate::color::ToColor;
+use crate::theme::{Style, Theme};
+use crate::{html, human, json, markdown, Error, Render};
 
 /// Construct a new, empty span
 pub fn span() -> Span {
@@ -36,8 +37,13 @@ macro_rules! __inner_span {
 ///     let message = span!(fg = "red", [
 ///         text("hello"),
 ///     ]);
-///
 ///     out.print(message)?;
+///
+///     // use a named style from the target's theme
+///     let warning = span!(style = "warning", bold = false, [
+///         text("careful"),
+///     ]);
+///     out.print(warning)?;
 ///     Ok(())
 /// }
 /// ```
@@ -55,11 +61,8 @@ macro_rules! span {
 #[derive(Default)]
 pub struct Span {
     items: Vec<Box<dyn Render>>,
-    fg: Option<::termcolor::Color>,
-    bg: Option<::termcolor::Color>,
-    bold: bool,
-    underline: bool,
-    intense: bool,
+    style_name: Option<String>,
+    style: Style,
 }
 
 impl Span {
@@ -68,42 +71,92 @@ impl Span {
         self
     }
 
-    pub fn fg(mut self, color: &str) -> Result<Self, Error> {
-        self.fg = Some(color.parse()?);
+    /// Use a named style from the theme
+    ///
+    /// Attributes set explicitly on this span take precedence over the ones
+    /// of the style. Unknown style names are ignored.
+    pub fn style(mut self, name: &str) -> Result<Self, Error> {
+        self.style_name = Some(name.to_string());
         Ok(self)
     }
 
-    pub fn bg(mut self, color: &str) -> Result<Self, Error> {
-        self.bg = Some(color.parse()?);
+    /// Set foreground color
+    ///
+    /// See the [`color`](../color/index.html) module for supported values.
+    pub fn fg<C: ToColor>(mut self, color: C) -> Result<Self, Error> {
+        self.style = self.style.fg(color.to_color()?);
         Ok(self)
     }
 
+    /// Set background color
+    ///
+    /// See the [`color`](../color/index.html) module for supported values.
+    pub fn bg<C: ToColor>(mut self, color: C) -> Result<Self, Error> {
+        self.style = self.style.bg(color.to_color()?);
+        Ok(self)
+    }
+
+    /// Set bold
     pub fn bold(mut self, yes: bool) -> Result<Self, Error> {
-        self.bold = yes;
+        self.style = self.style.bold(yes);
         Ok(self)
     }
 
+    /// Set dim, for secondary information
+    pub fn dim(mut self, yes: bool) -> Result<Self, Error> {
+        self.style = self.style.dim(yes);
+        Ok(self)
+    }
+
+    /// Set italic
+    pub fn italic(mut self, yes: bool) -> Result<Self, Error> {
+        self.style = self.style.italic(yes);
+        Ok(self)
+    }
+
+    /// Set underline
     pub fn underline(mut self, yes: bool) -> Result<Self, Error> {
-        self.underline = yes;
+        self.style = self.style.underline(yes);
+        Ok(self)
+    }
+
+    /// Set strikethrough
+    pub fn strikethrough(mut self, yes: bool) -> Result<Self, Error> {
+        self.style = self.style.strikethrough(yes);
         Ok(self)
     }
 
+    /// Set reverse video, i.e., swap foreground and background colors
+    ///
+    /// See [`Style::reverse`](../theme/struct.Style.html#method.reverse) for
+    /// how this is emulated.
+    pub fn reverse(mut self, yes: bool) -> Result<Self, Error> {
+        self.style = self.style.reverse(yes);
+        Ok(self)
+    }
+
+    /// Set intense, i.e., use the bright variant of the colors
     pub fn intense(mut self, yes: bool) -> Result<Self, Error> {
-        self.intense = yes;
+        self.style = self.style.intense(yes);
         Ok(self)
     }
+
+    /// The named style with the explicitly set attributes applied
+    fn resolve_style(&self, theme: &Theme) -> Style {
+        match &self.style_name {
+            Some(name) => match theme.get(name) {
+                Some(base) => base.merge(&self.style),
+                None => self.style.clone(),
+            },
+            None => self.style.clone(),
+        }
+    }
 }
 
 impl Render for Span {
     fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
-        fmt.set_color(
-            ColorSpec::new()
-                .set_fg(self.fg)
-                .set_bg(self.bg)
-                .set_bold(self.bold)
-                .set_underline(self.underline)
-                .set_intense(self.intense),
-        )?;
+        let style = self.resolve_style(fmt.theme());
+        fmt.set_style(&style)?;
         for item in &self.items {
             item.render_for_humans(fmt)?;
         }
@@ -121,6 +174,32 @@ impl Render for Span {
         }
         Ok(())
     }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        let mut attributes = String::new();
+        if let Some(name) = &self.style_name {
+            attributes.push_str(&format!(" class=\"{}\"", html::class_name(name)));
+        }
+        if self.style != Style::default() {
+            let css = html::css(&self.style.to_color_spec());
+            attributes.push_str(&format!(" style=\"{}\"", css));
+        }
+
+        fmt.write_raw(format!("<span{}>", attributes))?;
+        for item in &self.items {
+            item.render_html(fmt)?;
+        }
+        fmt.write_raw("</span>")
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        let mut content = String::new();
+        for item in &self.items {
+            content.push_str(&fmt.capture(item.as_ref())?);
+        }
+        let spec = self.resolve_style(fmt.theme()).to_color_spec();
+        fmt.write_raw(markdown::emphasize(&spec, &content))
+    }
 }
 
 #[cfg(test)]
@@ -206,5 +285,111 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_dim_output() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let out = crate::new().add_target(test_target.target())?;
+
+        out.print(span().add_item("hello").dim(true)?)?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}[0m\u{1b}[2mhello\u{1b}[0m\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_italic_output() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let out = crate::new().add_target(test_target.target())?;
+
+        out.print(span().add_item("hello").italic(true)?)?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}[0m\u{1b}[3mhello\u{1b}[0m\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_strikethrough_output() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let out = crate::new().add_target(test_target.target())?;
+
+        out.print(span().add_item("hello").strikethrough(true)?)?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}[0m\u{1b}[9mhello\u{1b}[0m\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_reverse_output() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let out = crate::new().add_target(test_target.target())?;
+
+        out.print(span().add_item("hello").fg("green")?.reverse(true)?)?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}[0m\u{1b}[30m\u{1b}[42mhello\u{1b}[0m\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_named_style_output() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let theme = "error = bold red\nfancy = underline".parse()?;
+        let out = crate::new().add_target(test_target.target().with_theme(theme)?)?;
+
+        out.print(span().add_item("a").style("error")?)?;
+        out.print(span().add_item("b").style("error")?.fg("blue")?)?;
+        out.print(span().add_item("c").style("fancy")?.underline(false)?)?;
+        out.print(span().add_item("d").style("unknown")?)?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}[0m\u{1b}[1m\u{1b}[31ma\u{1b}[0m\n\
+             \u{1b}[0m\u{1b}[1m\u{1b}[34mb\u{1b}[0m\n\
+             \u{1b}[0mc\u{1b}[0m\n\
+             \u{1b}[0md\u{1b}[0m\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_downgraded_colors() -> Result<(), Error> {
+        use crate::color::{Color, ColorSupport};
+
+        let test_target = human::test_with_color();
+        let target = test_target
+            .target()
+            .with_color_support(ColorSupport::Ansi256)?;
+        let out = crate::new().add_target(target)?;
+
+        out.print(span().add_item("hex").fg("#ff8800")?)?;
+        out.print(span().add_item("rgb").fg((0, 0, 0))?)?;
+        out.print(span().add_item("color").bg(Color::Rgb(128, 128, 128))?)?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}[0m\u{1b}[38;5;208mhex\u{1b}[0m\n\
+             \u{1b}[0m\u{1b}[38;5;16mrgb\u{1b}[0m\n\
+             \u{1b}[0m\u{1b}[48;5;244mcolor\u{1b}[0m\n"
+        );
+        Ok(())
+    }
+
     // TODO: Add proptest tests
 }
@@ -0,0 +1,215 @@
+use super::units::format_duration;
+use crate::glyphs::{self, Glyphs};
+use crate::layout::width;
+use crate::{human, json, Error, Render};
+use serde_json::json;
+use std::time::Duration;
+
+/// Render a status line like `✓ Compiled foo`
+///
+/// The icon is taken from the target's [glyphs](../glyphs/index.html), so it
+/// becomes `[ OK ]` where unicode isn't supported. It is colored with the
+/// theme style matching the status (`success`, `warning`, `error`, or
+/// `info`). Instead of an icon, a verb can be shown, right-justified like
+/// cargo does. In JSON, this is an object like
+/// `{"status": "ok", "message": "Compiled foo"}`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::{status, Status}};
+/// # use std::time::Duration;
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target().with_width(30)?)?;
+/// out.print(status(Status::Ok, "Compiled foo").elapsed(Duration::from_secs(72)))?;
+/// out.print(status(Status::Info, "foo v0.1").verb("Compiling"))?;
+/// out.print(status(Status::Error, "Tests failed").ascii(true))?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "\
+/// # ✓ Compiled foo          1m 12s
+/// #    Compiling foo v0.1
+/// # [FAIL] Tests failed
+/// # ");
+/// # Ok(()) }
+/// ```
+pub fn status<T: Render + 'static>(status: Status, message: T) -> StatusLine {
+    StatusLine {
+        status,
+        message: Box::new(message),
+        verb: None,
+        elapsed: None,
+        ascii: None,
+    }
+}
+
+/// The kind of a [`status`] line
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde_derive::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// Something succeeded
+    Ok,
+    /// Something worked, but not quite as expected
+    Warning,
+    /// Something failed
+    Error,
+    /// Something happened
+    Info,
+}
+
+impl Status {
+    fn style(self) -> &'static str {
+        match self {
+            Status::Ok => "success",
+            Status::Warning => "warning",
+            Status::Error => "error",
+            Status::Info => "info",
+        }
+    }
+
+    fn icon(self, glyphs: &Glyphs) -> &'static str {
+        match self {
+            Status::Ok => glyphs.success,
+            Status::Warning => glyphs.warning,
+            Status::Error => glyphs.error,
+            Status::Info => glyphs.info,
+        }
+    }
+}
+
+pub struct StatusLine {
+    status: Status,
+    message: Box<dyn Render>,
+    verb: Option<String>,
+    elapsed: Option<Duration>,
+    ascii: Option<bool>,
+}
+
+/// Width verbs are right-justified to, same as cargo's
+const VERB_WIDTH: usize = 12;
+
+impl StatusLine {
+    /// Show a verb like `Compiling` instead of the icon
+    pub fn verb<T: AsRef<str>>(mut self, verb: T) -> Self {
+        self.verb = Some(verb.as_ref().to_string());
+        self
+    }
+
+    /// Show how long something took at the end of the line
+    pub fn elapsed(mut self, elapsed: Duration) -> Self {
+        self.elapsed = Some(elapsed);
+        self
+    }
+
+    /// Use ASCII labels like `[ OK ]` instead of icons
+    ///
+    /// By default, this depends on what the target supports.
+    pub fn ascii(mut self, yes: bool) -> Self {
+        self.ascii = Some(yes);
+        self
+    }
+}
+
+impl Render for StatusLine {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let style = self.status.style();
+        let prefix = match &self.verb {
+            Some(verb) => {
+                let padding = VERB_WIDTH.saturating_sub(width(verb));
+                fmt.write(" ".repeat(padding))?;
+                fmt.write_styled(style, verb.as_str())?;
+                padding + width(verb)
+            }
+            None => {
+                let icon = self.status.icon(glyphs::select(fmt, self.ascii));
+                fmt.write_styled(style, icon)?;
+                width(icon)
+            }
+        };
+        fmt.write(" ")?;
+
+        let message = fmt.capture(self.message.as_ref())?.trim_end();
+        message.render(fmt)?;
+
+        if let Some(elapsed) = self.elapsed {
+            let elapsed = format_duration(elapsed, 2);
+            let used = prefix + 1 + message.lines().last().map_or(0, |line| line.width());
+            let padding = match fmt.width() {
+                Some(columns) => columns.saturating_sub(used + width(&elapsed)).max(2),
+                None => 2,
+            };
+            fmt.write(" ".repeat(padding))?;
+            fmt.write_styled("dim", elapsed)?;
+        }
+        Ok(())
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        let mut value = json!({
+            "status": self.status,
+            "message": json::Formatter::capture(self.message.as_ref())?,
+        });
+        if let Some(verb) = &self.verb {
+            value["verb"] = json!(verb);
+        }
+        if let Some(elapsed) = self.elapsed {
+            value["elapsed"] = json!(elapsed.as_secs_f64());
+        }
+        fmt.write(&value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{status, Status};
+    use crate::{human, json, Error, Render};
+    use std::time::Duration;
+
+    #[test]
+    fn colors_icons_and_verbs() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let mut fmt = test_target.formatter();
+        status(Status::Ok, "done").render_for_humans(&mut fmt)?;
+        fmt.write("\n")?;
+        status(Status::Warning, "x")
+            .verb("Checking")
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            test_target.to_string(),
+            "\u{1b}[0m\u{1b}[1m\u{1b}[32m✓\u{1b}[0m done\n    \
+             \u{1b}[0m\u{1b}[1m\u{1b}[33mChecking\u{1b}[0m x"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn separates_elapsed_time_without_width() -> Result<(), Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        status(Status::Info, "a")
+            .ascii(true)
+            .elapsed(Duration::from_millis(1500))
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(human_output.to_string(), "[INFO] a  1s 500ms");
+        Ok(())
+    }
+
+    #[test]
+    fn renders_json_objects() -> Result<(), Error> {
+        let json = json::test();
+        let mut fmt = json.formatter();
+        status(Status::Error, "failed")
+            .verb("Testing")
+            .elapsed(Duration::from_millis(500))
+            .render_json(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            json.to_string(),
+            "{\"elapsed\":0.5,\"message\":\"failed\",\"status\":\"error\",\"verb\":\"Testing\"}"
+        );
+        Ok(())
+    }
+}
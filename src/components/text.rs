@@ -1,4 +1,4 @@
-use crate::{human, json, Error, Render};
+use crate::{html, human, json, markdown, Error, Render};
 
 /// Render some text
 pub fn text<T: AsRef<str>>(input: T) -> Text {
@@ -25,6 +25,15 @@ impl Render for Text {
         fmt.write(self)?;
         Ok(())
     }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        fmt.write_raw(html::escape(&self.0).replace('\n', "<br>\n"))
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        // Two trailing spaces make a line break instead of a space
+        fmt.write_raw(markdown::escape(&self.0).replace('\n', "  \n"))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,207 @@
+use crate::glyphs::{self, Glyphs};
+use crate::{human, json, Error, Render};
+use serde_json::{json, Value};
+
+/// Render a tree, with `label` as the root node
+///
+/// Children are connected to their parent with box drawing characters, or
+/// ASCII if the target doesn't support unicode (see [`Tree::ascii`]). In
+/// JSON, each node is an object with a `label` and its `children`, and
+/// `duplicate` or `collapsed` set to `true` if the node is marked like that.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::{tree, text}};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(
+///     tree(text("convey"))
+///         .child(tree(text("serde")).leaf(text("serde_derive")))
+///         .child(tree(text("serde_json")).child(tree(text("serde")).duplicate())),
+/// )?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "\
+/// # convey
+/// # ├── serde
+/// # │   └── serde_derive
+/// # └── serde_json
+/// #     └── serde (*)
+/// # ");
+/// # Ok(()) }
+/// ```
+pub fn tree<T: Render + 'static>(label: T) -> Tree {
+    Tree {
+        label: Box::new(label),
+        children: Vec::new(),
+        duplicate: false,
+        collapsed: false,
+        ascii: None,
+    }
+}
+
+pub struct Tree {
+    label: Box<dyn Render>,
+    children: Vec<Tree>,
+    duplicate: bool,
+    collapsed: bool,
+    ascii: Option<bool>,
+}
+
+impl Tree {
+    /// Add a child node
+    pub fn child(mut self, child: Tree) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Add a child node without children of its own
+    pub fn leaf<T: Render + 'static>(self, label: T) -> Self {
+        self.child(tree(label))
+    }
+
+    /// Mark this node as already shown elsewhere in the tree
+    ///
+    /// Like in `cargo tree`, the label is followed by `(*)` and children are
+    /// left out. They are still included in JSON.
+    pub fn duplicate(mut self) -> Self {
+        self.duplicate = true;
+        self
+    }
+
+    /// Hide the children of this node from humans
+    ///
+    /// An ellipsis is shown in their place. They are still included in JSON.
+    pub fn collapsed(mut self) -> Self {
+        self.collapsed = true;
+        self
+    }
+
+    /// Draw connectors with ASCII characters instead of box drawing ones
+    ///
+    /// By default, this depends on what the target supports. This applies to
+    /// the whole tree when set on the root node.
+    pub fn ascii(mut self, yes: bool) -> Self {
+        self.ascii = Some(yes);
+        self
+    }
+
+    fn render_node(
+        &self,
+        fmt: &mut human::Formatter,
+        glyphs: &Glyphs,
+        prefix: &str,
+        last: Option<bool>,
+    ) -> Result<(), Error> {
+        let (connector, continuation) = match last {
+            None => ("", ""),
+            Some(false) => (glyphs.tree_branch, glyphs.tree_vertical),
+            Some(true) => (glyphs.tree_last_branch, glyphs.tree_space),
+        };
+
+        let lines = fmt.capture(self.label.as_ref())?.trim_end();
+        fmt.write_styled("dim", format!("{}{}", prefix, connector))?;
+        for (i, line) in lines.lines().iter().enumerate() {
+            if i > 0 {
+                fmt.write("\n")?;
+                fmt.write_styled("dim", format!("{}{}", prefix, continuation))?;
+            }
+            line.render(fmt)?;
+        }
+        if self.duplicate {
+            fmt.write(" ")?;
+            fmt.write_styled("dim", "(*)")?;
+            return Ok(());
+        }
+
+        let prefix = format!("{}{}", prefix, continuation);
+        if self.collapsed && !self.children.is_empty() {
+            fmt.write("\n")?;
+            fmt.write_styled(
+                "dim",
+                format!("{}{}{}", prefix, glyphs.tree_last_branch, glyphs.ellipsis),
+            )?;
+            return Ok(());
+        }
+        for (i, child) in self.children.iter().enumerate() {
+            fmt.write("\n")?;
+            child.render_node(fmt, glyphs, &prefix, Some(i + 1 == self.children.len()))?;
+        }
+        Ok(())
+    }
+
+    fn to_json(&self) -> Result<Value, Error> {
+        let children = self
+            .children
+            .iter()
+            .map(Tree::to_json)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut node = json!({
+            "label": json::Formatter::capture(self.label.as_ref())?,
+            "children": children,
+        });
+        if self.duplicate {
+            node["duplicate"] = json!(true);
+        }
+        if self.collapsed {
+            node["collapsed"] = json!(true);
+        }
+        Ok(node)
+    }
+}
+
+impl Render for Tree {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let glyphs = glyphs::select(fmt, self.ascii);
+        self.render_node(fmt, glyphs, "", None)
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(&self.to_json()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::tree;
+    use crate::components::text;
+    use crate::{human, json, Error, Render};
+
+    #[test]
+    fn renders_ascii_trees() -> Result<(), Error> {
+        let human_output = human::test();
+        let mut fmt = human_output.formatter();
+        tree(text("root"))
+            .child(tree(text("a\nb")).leaf(text("c")))
+            .child(tree(text("d")).leaf(text("e")).collapsed())
+            .ascii(true)
+            .render_for_humans(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            human_output.to_string(),
+            "root\n|-- a\n|   b\n|   `-- c\n`-- d\n    `-- ..."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_nested_json_objects() -> Result<(), Error> {
+        let json = json::test();
+        let mut fmt = json.formatter();
+        tree(text("root"))
+            .child(tree(1).leaf(2).collapsed())
+            .child(tree(3).leaf(4).duplicate())
+            .render_json(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(
+            json.to_string(),
+            "{\"children\":[\
+             {\"children\":[{\"children\":[],\"label\":2}],\"collapsed\":true,\"label\":1},\
+             {\"children\":[{\"children\":[],\"label\":4}],\"duplicate\":true,\"label\":3}\
+             ],\"label\":\"root\"}"
+        );
+        Ok(())
+    }
+}
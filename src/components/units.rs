@@ -0,0 +1,410 @@
+use crate::{human, json, Error, Render};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Render a number of bytes in a human-readable size like `1.2 MiB`
+///
+/// Uses binary units (KiB, MiB, …) by default. In JSON, this is just the
+/// number of bytes.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::bytes};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(bytes(1_258_291))?;
+/// out.print(bytes(1_258_291).binary(false).precision(2))?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "1.2 MiB\n1.26 MB\n");
+/// # Ok(()) }
+/// ```
+pub fn bytes(bytes: u64) -> Bytes {
+    Bytes {
+        bytes,
+        binary: true,
+        precision: 1,
+    }
+}
+
+pub struct Bytes {
+    bytes: u64,
+    binary: bool,
+    precision: usize,
+}
+
+impl Bytes {
+    /// Use binary units (KiB = 1024 bytes) or decimal ones (kB = 1000 bytes)
+    pub fn binary(mut self, yes: bool) -> Self {
+        self.binary = yes;
+        self
+    }
+
+    /// Set the number of decimal places (default: 1)
+    pub fn precision(mut self, digits: usize) -> Self {
+        self.precision = digits;
+        self
+    }
+}
+
+impl Render for Bytes {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let (base, units) = if self.binary {
+            (1024.0, ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"])
+        } else {
+            (1000.0, ["B", "kB", "MB", "GB", "TB", "PB", "EB"])
+        };
+
+        let mut value = self.bytes as f64;
+        let mut unit = 0;
+        while value >= base && unit < units.len() - 1 {
+            value /= base;
+            unit += 1;
+        }
+        // Values just below the next unit would be shown as e.g. `1024.0 KiB`
+        let scale = 10f64.powi(self.precision.min(16) as i32);
+        if unit > 0 && (value * scale).round() / scale >= base && unit < units.len() - 1 {
+            value /= base;
+            unit += 1;
+        }
+
+        if unit == 0 {
+            fmt.write(format!("{} {}", self.bytes, units[0]))
+        } else {
+            let precision = self.precision;
+            fmt.write(format!("{:.*} {}", precision, value, units[unit]))
+        }
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(&self.bytes)
+    }
+}
+
+/// Render a duration like `3m 12s`
+///
+/// Only the two largest units are shown by default. In JSON, this is the
+/// number of seconds (or milliseconds, see [`HumanDuration::millis`]).
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::duration};
+/// # use std::time::Duration;
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(duration(Duration::from_millis(192_250)))?;
+/// out.print(duration(Duration::from_millis(192_250)).precision(3))?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "3m 12s\n3m 12s 250ms\n");
+/// # Ok(()) }
+/// ```
+pub fn duration(duration: Duration) -> HumanDuration {
+    HumanDuration {
+        duration,
+        precision: 2,
+        millis: false,
+    }
+}
+
+pub struct HumanDuration {
+    duration: Duration,
+    precision: usize,
+    millis: bool,
+}
+
+impl HumanDuration {
+    /// Set how many units are shown at most (default: 2)
+    pub fn precision(mut self, units: usize) -> Self {
+        self.precision = units.max(1);
+        self
+    }
+
+    /// Write milliseconds instead of seconds to JSON
+    pub fn millis(mut self, yes: bool) -> Self {
+        self.millis = yes;
+        self
+    }
+}
+
+/// Format a duration using at most `precision` units, like `3m 12s`
+pub(crate) fn format_duration(duration: Duration, precision: usize) -> String {
+    const UNITS: [(&str, u128); 6] = [
+        ("d", 86_400_000_000),
+        ("h", 3_600_000_000),
+        ("m", 60_000_000),
+        ("s", 1_000_000),
+        ("ms", 1_000),
+        ("µs", 1),
+    ];
+
+    let mut rest = duration.as_micros();
+    let parts: Vec<_> = UNITS
+        .iter()
+        .map(|&(unit, size)| {
+            let amount = rest / size;
+            rest %= size;
+            (amount, unit)
+        })
+        .skip_while(|&(amount, _)| amount == 0)
+        .take(precision)
+        .filter(|&(amount, _)| amount > 0)
+        .map(|(amount, unit)| format!("{}{}", amount, unit))
+        .collect();
+
+    if parts.is_empty() {
+        String::from("0s")
+    } else {
+        parts.join(" ")
+    }
+}
+
+impl Render for HumanDuration {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        fmt.write(format_duration(self.duration, self.precision))
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        if self.millis {
+            fmt.write(&self.duration.as_millis())
+        } else {
+            fmt.write(&self.duration.as_secs_f64())
+        }
+    }
+}
+
+/// Render a number of things, like `1 file` or `5 files`
+///
+/// In JSON, this is just the number.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::count};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(count(1, "file", "files"))?;
+/// out.print(count(12_345, "file", "files"))?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "1 file\n12,345 files\n");
+/// # Ok(()) }
+/// ```
+pub fn count<S: AsRef<str>, P: AsRef<str>>(count: u64, singular: S, plural: P) -> Count {
+    Count {
+        count,
+        singular: singular.as_ref().to_string(),
+        plural: plural.as_ref().to_string(),
+        separator: Some(','),
+    }
+}
+
+pub struct Count {
+    count: u64,
+    singular: String,
+    plural: String,
+    separator: Option<char>,
+}
+
+impl Count {
+    /// Set the character that groups thousands (default: `,`)
+    pub fn separator(mut self, separator: Option<char>) -> Self {
+        self.separator = separator;
+        self
+    }
+}
+
+// `is_multiple_of` needs a newer compiler than this crate supports
+#[allow(clippy::manual_is_multiple_of)]
+fn group_thousands(number: u64, separator: Option<char>) -> String {
+    let digits = number.to_string();
+    let separator = match separator {
+        Some(separator) => separator,
+        None => return digits,
+    };
+
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+impl Render for Count {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let noun = if self.count == 1 {
+            &self.singular
+        } else {
+            &self.plural
+        };
+        fmt.write(format!(
+            "{} {}",
+            group_thousands(self.count, self.separator),
+            noun
+        ))
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(&self.count)
+    }
+}
+
+/// Render a point in time relative to now, like `2 hours ago`
+///
+/// In JSON, this is the number of seconds since the Unix epoch.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::{human, components::relative_time};
+/// # use std::time::{Duration, SystemTime};
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// let two_hours_ago = SystemTime::now() - Duration::from_secs(2 * 60 * 60);
+/// out.print(relative_time(two_hours_ago))?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "2 hours ago\n");
+/// # Ok(()) }
+/// ```
+pub fn relative_time(time: SystemTime) -> RelativeTime {
+    RelativeTime { time, now: None }
+}
+
+pub struct RelativeTime {
+    time: SystemTime,
+    now: Option<SystemTime>,
+}
+
+impl RelativeTime {
+    /// Compare to this point in time instead of the current time
+    pub fn relative_to(mut self, now: SystemTime) -> Self {
+        self.now = Some(now);
+        self
+    }
+}
+
+impl Render for RelativeTime {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        const UNITS: [(&str, &str, u64); 6] = [
+            ("year", "years", 365 * 24 * 60 * 60),
+            ("month", "months", 30 * 24 * 60 * 60),
+            ("week", "weeks", 7 * 24 * 60 * 60),
+            ("day", "days", 24 * 60 * 60),
+            ("hour", "hours", 60 * 60),
+            ("minute", "minutes", 60),
+        ];
+
+        let now = self.now.unwrap_or_else(SystemTime::now);
+        let (difference, future) = match now.duration_since(self.time) {
+            Ok(difference) => (difference, false),
+            Err(e) => (e.duration(), true),
+        };
+        let seconds = difference.as_secs();
+
+        let unit = UNITS.iter().find(|&&(_, _, size)| seconds >= size);
+        let (amount, singular, plural) = match unit {
+            Some(&(singular, plural, size)) => (seconds / size, singular, plural),
+            None => return fmt.write("just now"),
+        };
+        let text = count(amount, singular, plural);
+        if future {
+            fmt.write("in ")?;
+            text.render_for_humans(fmt)
+        } else {
+            text.render_for_humans(fmt)?;
+            fmt.write(" ago")
+        }
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        let seconds = match self.time.duration_since(UNIX_EPOCH) {
+            Ok(since) => since.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        };
+        fmt.write(&seconds)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bytes, count, duration, relative_time};
+    use crate::{human, json, Error, Render};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn render<T: Render>(item: T) -> Result<(String, String), Error> {
+        let human_output = human::test();
+        item.render_for_humans(&mut human_output.formatter())?;
+        let json_output = json::test();
+        item.render_json(&mut json_output.formatter())?;
+        Ok((human_output.to_string(), json_output.to_string()))
+    }
+
+    #[test]
+    fn renders_sizes() -> Result<(), Error> {
+        assert_eq!(render(bytes(0))?, ("0 B".into(), "0".into()));
+        assert_eq!(render(bytes(1023))?, ("1023 B".into(), "1023".into()));
+        assert_eq!(render(bytes(1536))?.0, "1.5 KiB");
+        assert_eq!(render(bytes(1536).binary(false))?.0, "1.5 kB");
+        assert_eq!(render(bytes(u64::MAX))?.0, "16.0 EiB");
+        assert_eq!(render(bytes(1_048_575))?.0, "1.0 MiB");
+        assert_eq!(render(bytes(1024 * 1024 * 1024 - 1))?.0, "1.0 GiB");
+        assert_eq!(render(bytes(1_048_575).precision(3))?.0, "1023.999 KiB");
+        assert_eq!(render(bytes(999_999).binary(false))?.0, "1.0 MB");
+        Ok(())
+    }
+
+    #[test]
+    fn renders_durations() -> Result<(), Error> {
+        let (human, json) = render(duration(Duration::from_secs(3 * 86_400 + 60)))?;
+        assert_eq!(human, "3d");
+        assert_eq!(json, "259260.0");
+        assert_eq!(render(duration(Duration::from_millis(250)))?.0, "250ms");
+        assert_eq!(render(duration(Duration::from_secs(0)))?.0, "0s");
+        assert_eq!(
+            render(duration(Duration::from_millis(1500)).millis(true))?,
+            ("1s 500ms".into(), "1500".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_counts() -> Result<(), Error> {
+        assert_eq!(render(count(0, "file", "files"))?.0, "0 files");
+        assert_eq!(
+            render(count(1_234_567, "file", "files").separator(Some('_')))?,
+            ("1_234_567 files".into(), "1234567".into())
+        );
+        assert_eq!(render(count(1000, "x", "xs").separator(None))?.0, "1000 xs");
+        Ok(())
+    }
+
+    #[test]
+    fn renders_relative_times() -> Result<(), Error> {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let at = |seconds: i64| {
+            let time = if seconds < 0 {
+                now - Duration::from_secs(-seconds as u64)
+            } else {
+                now + Duration::from_secs(seconds as u64)
+            };
+            relative_time(time).relative_to(now)
+        };
+
+        assert_eq!(render(at(-30))?.0, "just now");
+        assert_eq!(render(at(-60))?.0, "1 minute ago");
+        assert_eq!(render(at(-3 * 86_400))?.0, "3 days ago");
+        assert_eq!(render(at(2 * 3600 + 5))?.0, "in 2 hours");
+        assert_eq!(render(at(-400 * 86_400))?.0, "1 year ago");
+        assert_eq!(render(at(0))?.1, "1000000000");
+        Ok(())
+    }
+}
@@ -1,3 +1,6 @@
+// `failure_derive` generates its impls inside an anonymous const
+#![allow(non_local_definitions)]
+
 use failure::{Backtrace, Context, Fail};
 use serde_json::Error as JsonError;
 use std::fmt::{self, Display};
@@ -38,6 +41,9 @@ enum InnerError {
     #[fail(display = "{}", _0)]
     ParseColorError(ParseColorError),
 
+    #[fail(display = "Invalid color `{}`", _0)]
+    InvalidColor(String),
+
     #[fail(display = "Json error: {}", _0)]
     Json(JsonError),
 
@@ -50,6 +56,12 @@ enum InnerError {
     #[fail(display = "Error sending data to channel")]
     ChannelError(String),
 
+    #[fail(display = "Invalid theme: {}", _0)]
+    ThemeError(String),
+
+    #[fail(display = "Prompt failed: {}", _0)]
+    PromptError(String),
+
     #[cfg(feature = "log")]
     #[fail(display = "{}", _0)]
     SetLoggerError(log::SetLoggerError),
@@ -62,6 +74,24 @@ impl Error {
         }
     }
 
+    pub(crate) fn invalid_color(x: &str) -> Self {
+        Error {
+            inner: Context::new(InnerError::InvalidColor(x.to_string())),
+        }
+    }
+
+    pub(crate) fn theme_error(x: String) -> Self {
+        Error {
+            inner: Context::new(InnerError::ThemeError(x)),
+        }
+    }
+
+    pub(crate) fn prompt_error(x: String) -> Self {
+        Error {
+            inner: Context::new(InnerError::PromptError(x)),
+        }
+    }
+
     pub(crate) fn sync_error<T>(x: &PoisonError<T>) -> Self {
         Error {
             inner: Context::new(InnerError::SyncError(x.to_string())),
@@ -0,0 +1,229 @@
+//! Characters to draw things with
+//!
+//! Box drawing characters, check marks, and the like look great in modern
+//! terminals, but broken in some CI consoles and legacy ones. Human targets
+//! detect which characters are safe to use (see [`UnicodeSupport::detect`])
+//! and offer a matching [`Glyphs`] table, which is used by the built-in
+//! components and can be used by custom `Render` implementations as well.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate convey;
+//! use convey::{human, Error, Render};
+//!
+//! struct Done;
+//!
+//! impl Render for Done {
+//!     fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+//!         fmt.write_styled("success", fmt.glyphs().success)?;
+//!         fmt.write(" done")
+//!     }
+//!
+//!     fn render_json(&self, fmt: &mut convey::json::Formatter) -> Result<(), Error> {
+//!         fmt.write(&"done")
+//!     }
+//! }
+//! ```
+
+use crate::human;
+use std::env;
+
+/// Which characters a terminal is able to display
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UnicodeSupport {
+    /// Only ASCII
+    Ascii,
+    /// Unicode, including box drawing characters, but no emoji
+    Unicode,
+    /// Unicode and emoji
+    Emoji,
+}
+
+impl UnicodeSupport {
+    /// Detect what the current terminal supports
+    ///
+    /// This looks at the locale (`LC_ALL`, `LC_CTYPE`, and `LANG`) and at
+    /// environment variables set by terminal emulators. Set `CONVEY_UNICODE`
+    /// to `ascii`, `unicode`, or `emoji` to override this.
+    pub fn detect() -> Self {
+        let var = |name| env::var(name).unwrap_or_default();
+
+        match var("CONVEY_UNICODE").to_lowercase().as_str() {
+            "ascii" | "0" => return UnicodeSupport::Ascii,
+            "unicode" | "1" => return UnicodeSupport::Unicode,
+            "emoji" => return UnicodeSupport::Emoji,
+            _ => {}
+        }
+
+        let term = var("TERM");
+        let windows_terminal = env::var_os("WT_SESSION").is_some();
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .map(|name| var(name))
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_lowercase();
+        let utf8 = locale.contains("utf-8") || locale.contains("utf8");
+
+        if term == "dumb" || !(utf8 || windows_terminal) {
+            UnicodeSupport::Ascii
+        } else if term == "linux" || env::var_os("CI").is_some() {
+            UnicodeSupport::Unicode
+        } else if windows_terminal
+            || [
+                "iTerm.app",
+                "Apple_Terminal",
+                "WezTerm",
+                "vscode",
+                "ghostty",
+            ]
+            .contains(&var("TERM_PROGRAM").as_str())
+            || term == "xterm-kitty"
+        {
+            UnicodeSupport::Emoji
+        } else {
+            UnicodeSupport::Unicode
+        }
+    }
+
+    /// The glyphs to use with this level of support
+    pub fn glyphs(self) -> &'static Glyphs {
+        match self {
+            UnicodeSupport::Ascii => &Glyphs::ASCII,
+            UnicodeSupport::Unicode => &Glyphs::UNICODE,
+            UnicodeSupport::Emoji => &Glyphs::EMOJI,
+        }
+    }
+}
+
+/// A set of characters to draw things with
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Glyphs {
+    /// Something succeeded
+    pub success: &'static str,
+    /// Something needs attention
+    pub warning: &'static str,
+    /// Something failed
+    pub error: &'static str,
+    /// Something to note
+    pub info: &'static str,
+    /// Text was left out
+    pub ellipsis: &'static str,
+    /// Points from one thing to another
+    pub arrow: &'static str,
+    /// Horizontal line, e.g. for rules and borders
+    pub horizontal: &'static str,
+    /// Vertical line, e.g. for gutters and borders
+    pub vertical: &'static str,
+    /// Corners of a box, clockwise starting at the top left
+    pub corners: [&'static str; 4],
+    /// Connects a tree node that has siblings after it
+    pub tree_branch: &'static str,
+    /// Connects the last child of a tree node
+    pub tree_last_branch: &'static str,
+    /// Continues the line to the next sibling in a tree
+    pub tree_vertical: &'static str,
+    /// Indents the children of the last child in a tree
+    pub tree_space: &'static str,
+    /// Frames of a spinner animation
+    pub spinner: &'static [&'static str],
+}
+
+impl Glyphs {
+    /// Glyphs that work everywhere
+    pub const ASCII: Glyphs = Glyphs {
+        success: "[ OK ]",
+        warning: "[WARN]",
+        error: "[FAIL]",
+        info: "[INFO]",
+        ellipsis: "...",
+        arrow: "->",
+        horizontal: "-",
+        vertical: "|",
+        corners: ["+", "+", "+", "+"],
+        tree_branch: "|-- ",
+        tree_last_branch: "`-- ",
+        tree_vertical: "|   ",
+        tree_space: "    ",
+        spinner: &["|", "/", "-", "\\"],
+    };
+
+    /// Glyphs for terminals that support unicode
+    pub const UNICODE: Glyphs = Glyphs {
+        success: "✓",
+        warning: "⚠",
+        error: "✗",
+        info: "ℹ",
+        ellipsis: "…",
+        arrow: "→",
+        horizontal: "─",
+        vertical: "│",
+        corners: ["┌", "┐", "┘", "└"],
+        tree_branch: "├── ",
+        tree_last_branch: "└── ",
+        tree_vertical: "│   ",
+        tree_space: "    ",
+        spinner: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+    };
+
+    /// Glyphs for terminals that support unicode and emoji
+    pub const EMOJI: Glyphs = Glyphs {
+        success: "✅",
+        warning: "⚠️",
+        error: "❌",
+        info: "ℹ️",
+        ..Glyphs::UNICODE
+    };
+}
+
+/// The glyphs a component should use, given its explicit ASCII setting
+pub(crate) fn select(fmt: &human::Formatter, ascii: Option<bool>) -> &'static Glyphs {
+    match ascii {
+        Some(true) => &Glyphs::ASCII,
+        Some(false) => &Glyphs::UNICODE,
+        None => fmt.glyphs(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::UnicodeSupport;
+    use crate::components::{panel, rule, status, text, tree, Status};
+    use crate::{human, Error};
+
+    #[test]
+    fn falls_back_to_ascii() -> Result<(), Error> {
+        let test_target = human::test();
+        let target = test_target
+            .target()
+            .with_width(12)?
+            .with_unicode_support(UnicodeSupport::Ascii)?;
+        let out = crate::new().add_target(target)?;
+        out.print(tree(text("root")).leaf(text("a")).leaf(text("b")))?;
+        out.print(status(Status::Ok, "Done"))?;
+        out.print(rule())?;
+        out.print(panel(text("hi")))?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "root\n|-- a\n`-- b\n[ OK ] Done\n------------\n+----------+\n| hi       |\n+----------+\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn explicit_setting_wins() -> Result<(), Error> {
+        let test_target = human::test();
+        let target = test_target
+            .target()
+            .with_unicode_support(UnicodeSupport::Ascii)?;
+        let out = crate::new().add_target(target)?;
+        out.print(status(Status::Ok, "Done").ascii(false))?;
+        out.flush()?;
+
+        assert_eq!(test_target.to_string(), "✓ Done\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,415 @@
+//! HTML output
+//!
+//! Items are rendered with [`Render::render_html`]. By default, that is an
+//! item's human output, with its colors as inline styles. Components that
+//! have an HTML equivalent render to that instead: spans become `<span>`
+//! elements with a class for their named style, links become `<a>`, lists
+//! `<ul>` or `<ol>`, key-value pairs and columns `<table>`, and code `<pre>`.
+//!
+//! [`file`] writes a full document, with the styles of the target's theme as
+//! CSS classes. [`fragment`] only writes the items, e.g. to include them in a
+//! page of your own, together with the CSS from [`stylesheet`].
+//!
+//! [`Render::render_html`]: ../trait.Render.html#method.render_html
+
+use crate::color::to_rgb;
+use crate::theme::Theme;
+use crate::worker::{Backend, Worker};
+use crate::{human, Error, Render, Target};
+use failure::ResultExt;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use termcolor::ColorSpec;
+
+/// Create a new HTML output that writes a full document to a file
+///
+/// An existing file is overwritten. The document is finished when the
+/// target is dropped.
+pub fn file<T: AsRef<Path>>(name: T) -> Result<Target, Error> {
+    let path = name.as_ref().to_path_buf();
+    let formatter = Formatter::init_with(true, move || {
+        use std::fs::File;
+        use std::io::BufWriter;
+
+        let file = File::create(&path)
+            .with_context(|_| format!("Can't open file `{}` as HTML target", path.display()))?;
+        Ok(BufWriter::new(file))
+    })?;
+    Ok(Target::html(formatter))
+}
+
+/// Create a new HTML output that writes items to `writer`, without the rest
+/// of a document
+pub fn fragment<W: Write + Send + 'static>(writer: W) -> Result<Target, Error> {
+    let formatter = Formatter::init_with(false, || Ok(writer))?;
+    Ok(Target::html(formatter))
+}
+
+pub use self::test_helper::test;
+
+/// HTML formatter
+pub struct Formatter {
+    inner: Backend,
+    theme: Arc<Theme>,
+    /// Whether to write a full document instead of a fragment
+    document: bool,
+    /// Whether the start of the document was written
+    started: bool,
+}
+
+impl Formatter {
+    pub(crate) fn init_with<W: Write, F: FnOnce() -> Result<W, Error> + Send + 'static>(
+        document: bool,
+        init: F,
+    ) -> Result<Self, Error> {
+        Ok(Formatter {
+            inner: Backend::Worker(Arc::new(Worker::init_with(init)?)),
+            theme: Arc::new(Theme::default()),
+            document,
+            started: false,
+        })
+    }
+
+    /// The theme used to look up named styles
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub(crate) fn set_theme(&mut self, theme: Theme) {
+        self.theme = Arc::new(theme);
+    }
+
+    /// Write text, escaping characters that have a meaning in HTML
+    pub fn write<T: AsRef<str>>(&self, text: T) -> Result<(), Error> {
+        self.write_raw(escape(text.as_ref()))
+    }
+
+    /// Write HTML as it is
+    pub fn write_raw<T: Into<String>>(&self, html: T) -> Result<(), Error> {
+        self.inner.write(html.into())
+    }
+
+    /// Write text with a named style from the theme
+    ///
+    /// This is a `<span>` with the style's name as class, see
+    /// [`class_name`](fn.class_name.html).
+    pub fn write_styled<T: AsRef<str>>(&self, style: &str, text: T) -> Result<(), Error> {
+        self.write_raw(format!(
+            "<span class=\"{}\">{}</span>",
+            class_name(style),
+            escape(text.as_ref())
+        ))
+    }
+
+    /// Write the human output of an item, keeping its styling
+    ///
+    /// This is what [`Render::render_html`] does by default. Output that
+    /// spans multiple lines is put into a `<pre>` element.
+    ///
+    /// [`Render::render_html`]: ../trait.Render.html#method.render_html
+    pub fn write_human<R: Render + ?Sized>(&self, item: &R) -> Result<(), Error> {
+        let captured = human::Formatter::detached(self.theme.clone())
+            .capture(item)?
+            .trim_end();
+        let multiline = captured.lines().len() > 1;
+
+        let mut html = String::new();
+        if multiline {
+            html.push_str("<pre>");
+        }
+        for (i, line) in captured.lines().iter().enumerate() {
+            if i > 0 {
+                html.push('\n');
+            }
+            for (text, color, link) in line.segments() {
+                let mut text = escape(text);
+                if let Some(color) = color {
+                    text = format!("<span style=\"{}\">{}</span>", css(color), text);
+                }
+                if let Some(link) = link {
+                    text = format!("<a href=\"{}\">{}</a>", escape(link), text);
+                }
+                html.push_str(&text);
+            }
+        }
+        if multiline {
+            html.push_str("</pre>");
+        }
+        self.write_raw(html)
+    }
+
+    /// Render an item to a string instead of writing it
+    ///
+    /// This is useful for components that need to post-process the HTML of
+    /// their children.
+    pub fn capture<R: Render + ?Sized>(&self, item: &R) -> Result<String, Error> {
+        let captured = Arc::new(Mutex::new(String::new()));
+        let mut fmt = Formatter {
+            inner: Backend::Capture(captured.clone()),
+            theme: self.theme.clone(),
+            document: false,
+            started: false,
+        };
+        item.render_html(&mut fmt)?;
+
+        let captured = captured.lock().map_err(|e| Error::sync_error(&e))?;
+        Ok(captured.clone())
+    }
+
+    /// Write an item as a block of the document
+    pub(crate) fn write_item<R: Render + ?Sized>(&mut self, item: &R) -> Result<(), Error> {
+        if self.document && !self.started {
+            self.started = true;
+            self.write_raw(header(&self.theme))?;
+        }
+        self.write_raw("<div>")?;
+        item.render_html(self)?;
+        self.write_raw("</div>\n")
+    }
+
+    /// Immediately write all buffered output
+    pub fn flush(&self) -> Result<(), Error> {
+        self.inner.flush()
+    }
+}
+
+impl Drop for Formatter {
+    fn drop(&mut self) {
+        if self.document {
+            if !self.started {
+                let _ = self.write_raw(header(&self.theme));
+            }
+            let _ = self.write_raw("</body>\n</html>\n");
+        }
+    }
+}
+
+/// The CSS class used for a named style
+///
+/// Characters other than letters, digits, `-` and `_` are replaced with `-`,
+/// so `diff.insert` becomes `diff-insert`.
+pub fn class_name(style: &str) -> String {
+    style
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Escape characters that have a meaning in HTML
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// CSS declarations for a color spec
+pub(crate) fn css(spec: &ColorSpec) -> String {
+    let hex = |color, intense| {
+        let (r, g, b) = to_rgb(color, intense);
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    };
+
+    let mut declarations = Vec::new();
+    if let Some(&fg) = spec.fg() {
+        declarations.push(format!("color: {}", hex(fg, spec.intense())));
+    }
+    if let Some(&bg) = spec.bg() {
+        declarations.push(format!("background-color: {}", hex(bg, false)));
+    }
+    if spec.bold() {
+        declarations.push("font-weight: bold".to_string());
+    }
+    if spec.italic() {
+        declarations.push("font-style: italic".to_string());
+    }
+    if spec.dimmed() {
+        declarations.push("opacity: 0.7".to_string());
+    }
+    match (spec.underline(), spec.strikethrough()) {
+        (true, true) => declarations.push("text-decoration: underline line-through".into()),
+        (true, false) => declarations.push("text-decoration: underline".into()),
+        (false, true) => declarations.push("text-decoration: line-through".into()),
+        (false, false) => {}
+    }
+    declarations.join("; ")
+}
+
+/// CSS with a class for each of the theme's styles
+///
+/// This is what [`file`] puts into the document. Use it with [`fragment`] to
+/// style the items in a page of your own.
+pub fn stylesheet(theme: &Theme) -> String {
+    let mut css_rules = String::from("pre, code { font-family: monospace; }\n");
+    for (name, style) in theme.styles() {
+        css_rules.push_str(&format!(
+            ".{} {{ {} }}\n",
+            class_name(name),
+            css(&style.to_color_spec())
+        ));
+    }
+    css_rules
+}
+
+/// Start of a document, with the theme's styles as classes
+fn header(theme: &Theme) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<style>\n{}\
+         </style>\n</head>\n<body>\n",
+        stylesheet(theme)
+    )
+}
+
+mod test_helper {
+    use super::Formatter;
+    use crate::test_buffer::TestTarget;
+    use crate::Target;
+
+    /// Create a test output target that writes fragments
+    ///
+    /// Like [`json::test`](../json/fn.test.html), this is meant for tests and
+    /// panics instead of returning errors.
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// extern crate convey;
+    ///
+    /// fn main() -> Result<(), convey::Error> {
+    ///     let test_target = convey::html::test();
+    ///     let mut out = convey::new().add_target(test_target.target())?;
+    ///     out.print("<3")?;
+    ///     out.flush()?;
+    ///
+    ///     assert_eq!(test_target.to_string(), "<div>&lt;3</div>\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn test() -> TestTarget<Formatter> {
+        TestTarget::new(
+            |buffer| Formatter::init_with(false, || Ok(buffer)),
+            Target::html,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::{heading, kv, link, ordered_list, span, text, tree};
+    use crate::html;
+    use crate::theme::Theme;
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use predicates::prelude::*;
+
+    type Res = Result<(), ::failure::Error>;
+
+    #[test]
+    fn writes_human_output_with_inline_styles() -> Res {
+        let test_target = html::test();
+        let fmt = test_target.formatter();
+        let item = span()
+            .fg("red")?
+            .add_item(text("a <b>\n"))
+            .add_item(span().bold(true)?.add_item("c"));
+        fmt.write_human(&item)?;
+        fmt.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "<pre><span style=\"color: #cd0000\">a &lt;b&gt;</span>\n\
+             <span style=\"font-weight: bold\">c</span></pre>"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_components_to_elements() -> Res {
+        let test_target = html::test();
+        let out = crate::new().add_target(test_target.target())?;
+        out.print(heading(2, text("Results")))?;
+        out.print(
+            ordered_list()
+                .start(3)
+                .item(link("docs", "https://example.com/?a&b"))
+                .item(vec!["x"]),
+        )?;
+        out.print(kv().entry("name", span().style("code")?.add_item("convey")))?;
+        out.print(tree(text("root")).leaf(text("leaf")))?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "<div><h2>Results</h2></div>\n\
+             <div><ol start=\"3\"><li><a href=\"https://example.com/?a&amp;b\">docs</a></li>\
+             <li><ul><li>x</li></ul></li></ol></div>\n\
+             <div><table><tr><th>name</th><td><span class=\"code\">convey</span></td></tr></table></div>\n\
+             <div><pre>root\n<span style=\"color: #7f7f7f\">└── </span>leaf</pre></div>\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn captures_html() -> Res {
+        let test_target = html::test();
+        let fmt = test_target.formatter();
+        let captured = fmt.capture(&ordered_list().item("a<b"))?;
+        fmt.flush()?;
+
+        assert_eq!(captured, "<ol><li>a&lt;b</li></ol>");
+        assert_eq!(test_target.to_string(), "");
+        Ok(())
+    }
+
+    #[test]
+    fn writes_styles_for_fragments() {
+        let css = html::stylesheet(&Theme::default());
+        assert!(css.contains(".error { color: #cd0000; font-weight: bold }\n"));
+        assert!(css.contains(".diff-insert { "));
+    }
+
+    #[test]
+    fn writes_full_documents() -> Res {
+        let dir = TempDir::new()?;
+        let report = dir.child("report.html");
+
+        {
+            let out = crate::new().add_target(html::file(report.path())?)?;
+            out.print(span().style("error")?.add_item("oh no"))?;
+        }
+
+        report.assert(
+            predicate::str::starts_with("<!DOCTYPE html>")
+                .from_utf8()
+                .from_file_path(),
+        );
+        report.assert(
+            predicate::str::contains(".error { color: #cd0000; font-weight: bold }")
+                .from_utf8()
+                .from_file_path(),
+        );
+        report.assert(
+            predicate::str::ends_with(
+                "<body>\n<div><span class=\"error\">oh no</span></div>\n</body>\n</html>\n",
+            )
+            .from_utf8()
+            .from_file_path(),
+        );
+        Ok(())
+    }
+}
@@ -1,21 +1,201 @@
 //! Human output
 
-use crate::{Error, Target};
-use std::sync::Arc;
-use termcolor::{ColorChoice, ColorSpec, StandardStream, WriteColor};
+use crate::color::ColorSupport;
+use crate::glyphs::{Glyphs, UnicodeSupport};
+use crate::layout::{Captured, Line};
+use crate::pager::{self, Pager};
+use crate::theme::{Style, Theme};
+use crate::{Error, Render, Target};
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use termcolor::{ColorChoice, ColorSpec, HyperlinkSpec, NoColor, StandardStream, WriteColor};
 
 /// Construct a new human output target that writes to stdout
+///
+/// The colors the terminal supports are detected automatically, see
+/// [`ColorSupport::detect`](../color/enum.ColorSupport.html#method.detect), as
+/// are the characters it can display (see
+/// [`UnicodeSupport::detect`](../glyphs/enum.UnicodeSupport.html#method.detect)),
+/// whether it supports hyperlinks, how wide it is, and whether it is a
+/// terminal that [live regions](struct.LiveRegion.html) can be redrawn on.
 pub fn stdout() -> Result<Target, Error> {
-    let formatter = Formatter::init_with(|| Ok(StandardStream::stdout(ColorChoice::Auto)))?;
+    let mut formatter = Formatter::init_with(|| Ok(StandardStream::stdout(ColorChoice::Auto)))?;
+    detect_capabilities(&mut formatter);
+    Ok(Target::human(formatter))
+}
+
+/// Construct a new human output target that shows long output in a pager
+///
+/// When stdout is a terminal, output is buffered until it is flushed. If it
+/// gets taller than the terminal before that, it is piped to `$PAGER`
+/// (`less` by default) instead, and flushing waits for the pager to be
+/// closed. Like git, this sets `LESS=FRX` unless `LESS` is set. Set `PAGER`
+/// to an empty string to disable this.
+///
+/// Otherwise, this is the same as [`stdout`]. Capabilities are detected the
+/// same way, except that [live regions](struct.LiveRegion.html) can't be
+/// redrawn in a pager, and that hyperlinks are disabled, since not every
+/// version of `less` passes them through.
+pub fn paged_stdout() -> Result<Target, Error> {
+    let size = terminal_size::terminal_size()
+        .map(|(width, height)| (usize::from(width.0), usize::from(height.0)));
+    let ((width, height), command) = match (std::io::stdout().is_terminal(), size, pager::command())
+    {
+        (true, Some(size), Some(command)) => (size, command),
+        _ => return stdout(),
+    };
+
+    let colors = StandardStream::stdout(ColorChoice::Auto).supports_color();
+    let mut formatter =
+        Formatter::init_with(move || Ok(Pager::new(command, width, height, colors, io::stdout())))?;
+    detect_capabilities(&mut formatter);
+    formatter.set_interactive(false);
+    formatter.set_hyperlinks(false);
     Ok(Target::human(formatter))
 }
 
+/// Construct a new human output target that writes plain text to `writer`
+///
+/// Components are laid out as usual, but no escape sequences for colors,
+/// styles, or hyperlinks are ever written. This is useful for text that ends
+/// up in emails or files. Lines are not wrapped unless a width is set with
+/// [`Target::with_width`](../struct.Target.html#method.with_width).
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// use convey::components::{list, span};
+///
+/// # fn main() -> Result<(), convey::Error> {
+/// let path = std::env::temp_dir().join("convey-report.txt");
+/// {
+///     let file = std::fs::File::create(&path)?;
+///     let out = convey::new().add_target(convey::human::plain(file)?)?;
+///     out.print(list().item(span().fg("red")?.add_item("failed")))?;
+/// }
+/// assert_eq!(std::fs::read_to_string(&path)?, "- failed\n");
+/// # Ok(()) }
+/// ```
+pub fn plain<W: io::Write + Send + 'static>(writer: W) -> Result<Target, Error> {
+    let formatter = Formatter::init_with(|| Ok(NoColor::new(writer)))?;
+    Ok(Target::human(formatter))
+}
+
+/// Remove ANSI escape sequences from `text`
+///
+/// This is useful for output of other tools that contains colors, e.g. to
+/// measure it or to include it in plain text.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// let colored = "\x1b[1;31merror\x1b[0m: \x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\";
+/// assert_eq!(convey::human::strip_ansi(colored), "error: link");
+/// ```
+pub fn strip_ansi(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            // Control sequence: parameters and intermediates, then a final
+            // character in `@`..=`~`
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // Operating system command, e.g. a hyperlink: terminated by BEL
+            // or `ESC \`
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Other sequences are intermediates in ` `..=`/`, like the `(`
+            // of `ESC ( B`, then a single final character
+            Some(c) if (' '..='/').contains(&c) => {
+                for c in chars.by_ref() {
+                    if !(' '..='/').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+fn detect_capabilities(formatter: &mut Formatter) {
+    formatter.set_color_support(ColorSupport::detect());
+    formatter.set_unicode_support(UnicodeSupport::detect());
+    let interactive = std::io::stdout().is_terminal();
+    formatter.set_interactive(interactive);
+    // Streams without colors drop the escape sequences for links, too
+    let stream = StandardStream::stdout(ColorChoice::Auto);
+    formatter.set_hyperlinks(
+        interactive && stream.supports_hyperlinks() && terminal_supports_hyperlinks(),
+    );
+    formatter.set_width(terminal_size::terminal_size().map(|(width, _)| usize::from(width.0)));
+}
+
+/// Guess whether the terminal we run in supports OSC 8 hyperlinks
+///
+/// There is no way to query this, so we look for known terminal emulators.
+/// Set `FORCE_HYPERLINK` to `1` or `0` to override this.
+fn terminal_supports_hyperlinks() -> bool {
+    if let Ok(force) = env::var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+
+    let var = |name| env::var(name).unwrap_or_default();
+    let term_program = var("TERM_PROGRAM");
+    let term = var("TERM");
+    let vte_version: u32 = var("VTE_VERSION").parse().unwrap_or(0);
+
+    ["iTerm.app", "WezTerm", "vscode", "ghostty"].contains(&term_program.as_str())
+        || ["xterm-kitty", "foot", "alacritty"].contains(&term.as_str())
+        || vte_version >= 5000
+        || env::var_os("WT_SESSION").is_some()
+        || env::var_os("KONSOLE_VERSION").is_some()
+        || env::var_os("DOMTERM").is_some()
+}
+
 pub use self::test_helper::{test, test_with_color};
 
 /// Human output formatter
 #[derive(Clone)]
 pub struct Formatter {
-    inner: Arc<InternalFormatter>,
+    inner: Backend,
+    theme: Arc<Theme>,
+    color_support: ColorSupport,
+    unicode_support: UnicodeSupport,
+    hyperlinks: bool,
+    interactive: bool,
+    width: Option<usize>,
+}
+
+#[derive(Clone)]
+enum Backend {
+    Worker(Arc<InternalFormatter>),
+    /// Collects messages instead of sending them to the worker
+    Capture(Arc<Mutex<Vec<Message>>>),
 }
 
 impl Formatter {
@@ -23,19 +203,174 @@ impl Formatter {
         init: F,
     ) -> Result<Self, Error> {
         Ok(Formatter {
-            inner: Arc::new(InternalFormatter::init_with(init)?),
+            inner: Backend::Worker(Arc::new(InternalFormatter::init_with(init)?)),
+            theme: Arc::new(Theme::default()),
+            color_support: ColorSupport::TrueColor,
+            unicode_support: UnicodeSupport::Unicode,
+            hyperlinks: false,
+            interactive: false,
+            width: None,
         })
     }
 
+    /// A formatter that is only used to capture items, e.g. to convert them
+    /// to other formats
+    ///
+    /// It supports hyperlinks, so their targets are captured, too.
+    pub(crate) fn detached(theme: Arc<Theme>) -> Self {
+        Formatter {
+            inner: Backend::Capture(Arc::new(Mutex::new(Vec::new()))),
+            theme,
+            color_support: ColorSupport::TrueColor,
+            unicode_support: UnicodeSupport::Unicode,
+            hyperlinks: true,
+            interactive: false,
+            width: None,
+        }
+    }
+
+    /// The theme used to look up named styles
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub(crate) fn set_theme(&mut self, theme: Theme) {
+        self.theme = Arc::new(theme);
+    }
+
+    /// The colors the target is able to display
+    ///
+    /// Colors passed to [`set_color`](#method.set_color) are mapped to the
+    /// nearest supported ones automatically.
+    pub fn color_support(&self) -> ColorSupport {
+        self.color_support
+    }
+
+    pub(crate) fn set_color_support(&mut self, support: ColorSupport) {
+        self.color_support = support;
+    }
+
+    /// The characters the target is able to display
+    pub fn unicode_support(&self) -> UnicodeSupport {
+        self.unicode_support
+    }
+
+    pub(crate) fn set_unicode_support(&mut self, support: UnicodeSupport) {
+        self.unicode_support = support;
+    }
+
+    /// Characters to draw things with that the target is able to display
+    pub fn glyphs(&self) -> &'static Glyphs {
+        self.unicode_support.glyphs()
+    }
+
+    /// Whether the target is able to display hyperlinks
+    ///
+    /// If this is false, calls to [`set_hyperlink`](#method.set_hyperlink)
+    /// will be ignored, so you should include the URL in the text instead.
+    pub fn hyperlinks(&self) -> bool {
+        self.hyperlinks
+    }
+
+    pub(crate) fn set_hyperlinks(&mut self, yes: bool) {
+        self.hyperlinks = yes;
+    }
+
+    /// Whether the target is a terminal that output can be redrawn on
+    ///
+    /// If not, [live regions](struct.LiveRegion.html) append their updates
+    /// as regular output instead.
+    pub fn interactive(&self) -> bool {
+        self.interactive
+    }
+
+    pub(crate) fn set_interactive(&mut self, yes: bool) {
+        self.interactive = yes;
+    }
+
+    /// The number of columns available, if known
+    ///
+    /// Components that lay out text, like lists, wrap their lines to fit.
+    pub fn width(&self) -> Option<usize> {
+        self.width
+    }
+
+    pub(crate) fn set_width(&mut self, width: Option<usize>) {
+        self.width = width;
+    }
+
+    /// Render an item into lines of styled text instead of writing it
+    ///
+    /// This is useful for components that lay out other components, e.g. to
+    /// indent them. The item sees the same theme and capabilities as this
+    /// formatter.
+    pub(crate) fn capture<R: Render + ?Sized>(&self, item: &R) -> Result<Captured, Error> {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let mut fmt = Formatter {
+            inner: Backend::Capture(messages.clone()),
+            ..self.clone()
+        };
+        item.render_for_humans(&mut fmt)?;
+
+        let mut messages = messages.lock().map_err(|e| Error::sync_error(&e))?;
+        let mut captured = Captured::default();
+        let mut color = None;
+        let mut link = None;
+        for message in messages.drain(..) {
+            match message {
+                Message::Write(data) => {
+                    let text = String::from_utf8_lossy(&data);
+                    captured.write(&text, color.as_ref(), link.as_deref());
+                }
+                Message::SetColor(spec) => color = Some(spec).filter(|s| !s.is_none()),
+                Message::SetHyperlink(url) => link = url,
+                Message::ResetStyle => color = None,
+                Message::Live(..) | Message::EndLive(_) | Message::Flush | Message::Exit => {}
+            }
+        }
+        Ok(captured)
+    }
+
     /// Write to target
     pub fn write<D: Into<Vec<u8>>>(&self, data: D) -> Result<(), Error> {
         self.send(Message::Write(data.into()))?;
         Ok(())
     }
 
+    /// Write with a named style from the theme
+    ///
+    /// The style is reset afterwards. If the theme doesn't contain a style of
+    /// that name, the data is written without any styling.
+    pub fn write_styled<D: Into<Vec<u8>>>(&self, style: &str, data: D) -> Result<(), Error> {
+        match self.theme.get(style) {
+            Some(style) => {
+                self.set_style(style)?;
+                self.write(data)?;
+                self.reset()
+            }
+            None => self.write(data),
+        }
+    }
+
     /// Set color
     pub fn set_color(&self, spec: &ColorSpec) -> Result<(), Error> {
-        self.send(Message::SetColor(spec.clone()))?;
+        let spec = self.color_support.downgrade_spec(spec);
+        self.send(Message::SetColor(spec))?;
+        Ok(())
+    }
+
+    /// Set style
+    pub fn set_style(&self, style: &Style) -> Result<(), Error> {
+        self.set_color(&style.to_color_spec())
+    }
+
+    /// Start a hyperlink to `url`, or end the current one when passing `None`
+    ///
+    /// Everything written in between is the text of the link.
+    pub fn set_hyperlink(&self, url: Option<&str>) -> Result<(), Error> {
+        if self.hyperlinks {
+            self.send(Message::SetHyperlink(url.map(String::from)))?;
+        }
         Ok(())
     }
 
@@ -47,20 +382,173 @@ impl Formatter {
 
     /// Immediately write all buffered output
     pub fn flush(&self) -> Result<(), Error> {
+        let inner = match &self.inner {
+            Backend::Worker(inner) => inner,
+            Backend::Capture(_) => return Ok(()),
+        };
         self.send(Message::Flush)?;
 
-        match self.inner.receiver.recv() {
+        match inner.receiver.recv() {
             Ok(Response::Flushed) => Ok(()),
             msg => Err(Error::worker_error(format!("unexpected message {:?}", msg))),
         }
     }
 
     fn send(&self, msg: Message) -> Result<(), Error> {
-        self.inner.sender.send(msg)?;
+        match &self.inner {
+            Backend::Worker(inner) => inner.sender.send(msg)?,
+            Backend::Capture(messages) => {
+                let mut messages = messages.lock().map_err(|e| Error::sync_error(&e))?;
+                messages.push(msg);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lines at the bottom of the terminal that are updated in place
+///
+/// Create one with [`Output::live_region`](../struct.Output.html#method.live_region)
+/// to show e.g. the progress of several tasks running in parallel. Each line
+/// of the region shows one item, and everything printed while the region
+/// exists appears above it. When the region is finished (or dropped), its
+/// last state stays in the output.
+///
+/// Targets that aren't [interactive](struct.Formatter.html#method.interactive)
+/// can't be redrawn, so items are appended as regular output whenever they
+/// change instead.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// use convey::components::text;
+///
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = convey::human::test();
+/// let out = convey::new().add_target(test_target.target())?;
+/// let region = out.live_region()?;
+/// region.set(0, text("Building foo"))?;
+/// region.set(1, text("Building bar"))?;
+/// out.print(text("Finished foo"))?;
+/// region.set(0, text("Building baz"))?;
+/// region.finish()?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "\
+/// # Building foo
+/// # Building bar
+/// # Finished foo
+/// # Building baz
+/// # ");
+/// # Ok(()) }
+/// ```
+pub struct LiveRegion {
+    id: usize,
+    targets: Mutex<Vec<LiveTarget>>,
+    finished: bool,
+}
+
+struct LiveTarget {
+    formatter: Arc<Mutex<Formatter>>,
+    /// Rendered lines of each item
+    items: Vec<Vec<Line>>,
+}
+
+static NEXT_LIVE_REGION: AtomicUsize = AtomicUsize::new(0);
+
+impl LiveRegion {
+    pub(crate) fn new(formatters: Vec<Arc<Mutex<Formatter>>>) -> Self {
+        LiveRegion {
+            id: NEXT_LIVE_REGION.fetch_add(1, Ordering::SeqCst),
+            targets: Mutex::new(
+                formatters
+                    .into_iter()
+                    .map(|formatter| LiveTarget {
+                        formatter,
+                        items: Vec::new(),
+                    })
+                    .collect(),
+            ),
+            finished: false,
+        }
+    }
+
+    /// Show `item` on the line at `index`, replacing what was there before
+    ///
+    /// Lines that are wider than the terminal are cut off.
+    pub fn set<R: Render>(&self, index: usize, item: R) -> Result<(), Error> {
+        let mut targets = self.targets.lock().map_err(|e| Error::sync_error(&e))?;
+        for target in targets.iter_mut() {
+            let mut fmt = target.formatter.lock().map_err(|e| Error::sync_error(&e))?;
+            let mut lines = fmt.capture(&item)?.trim_end().lines().to_vec();
+            if let Some(width) = fmt.width() {
+                lines = lines.iter().map(|line| line.truncate(width)).collect();
+            }
+
+            if target.items.len() <= index {
+                target.items.resize(index + 1, Vec::new());
+            }
+            if target.items[index] == lines {
+                continue;
+            }
+
+            if !fmt.interactive {
+                for line in &lines {
+                    line.render(&mut fmt)?;
+                    fmt.write("\n")?;
+                }
+            }
+            target.items[index] = lines;
+            if fmt.interactive {
+                fmt.send(Message::Live(self.id, target.items.concat()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the line at `index`
+    pub fn remove(&self, index: usize) -> Result<(), Error> {
+        let mut targets = self.targets.lock().map_err(|e| Error::sync_error(&e))?;
+        for target in targets.iter_mut() {
+            if let Some(item) = target.items.get_mut(index) {
+                item.clear();
+            }
+            let fmt = target.formatter.lock().map_err(|e| Error::sync_error(&e))?;
+            if fmt.interactive {
+                fmt.send(Message::Live(self.id, target.items.concat()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop updating the region, leaving its lines in the output
+    pub fn finish(mut self) -> Result<(), Error> {
+        self.end()
+    }
+
+    fn end(&mut self) -> Result<(), Error> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+
+        let targets = self.targets.lock().map_err(|e| Error::sync_error(&e))?;
+        for target in targets.iter() {
+            let fmt = target.formatter.lock().map_err(|e| Error::sync_error(&e))?;
+            if fmt.interactive {
+                fmt.send(Message::EndLive(self.id))?;
+            }
+        }
         Ok(())
     }
 }
 
+impl Drop for LiveRegion {
+    fn drop(&mut self) {
+        let _ = self.end();
+    }
+}
+
 use crossbeam_channel as channel;
 use std::thread;
 
@@ -95,32 +583,63 @@ impl InternalFormatter {
                     |e| {
                         if cfg!(debug_assertions) {
                             eprintln!("{}", e)
-                        } else {
-                            ()
                         }
                     }
                 };
             }
 
-            loop {
-                match message_receiver.recv() {
-                    Ok(Message::Write(data)) => {
+            let mut live = LiveRegions::default();
+
+            while let Ok(message) = message_receiver.recv() {
+                // Regular output goes above the live regions, so they are
+                // removed before it is written and drawn again afterwards
+                match &message {
+                    Message::Flush | Message::Exit => {}
+                    _ => {
+                        let _ = live.hide(&mut buffer).map_err(maybe_log_error!());
+                    }
+                }
+
+                match message {
+                    Message::Write(data) => {
                         let _ = buffer.write_all(&data).map_err(maybe_log_error!());
+                        if let Some(&last) = data.last() {
+                            live.at_line_start = last == b'\n';
+                        }
                     }
-                    Ok(Message::SetColor(data)) => {
+                    Message::SetColor(data) => {
+                        live.styled = !data.is_none();
                         let _ = buffer.set_color(&data).map_err(maybe_log_error!());
                     }
-                    Ok(Message::ResetStyle) => {
+                    Message::SetHyperlink(Some(url)) => {
+                        live.linked = true;
+                        let link = HyperlinkSpec::open(url.as_bytes());
+                        let _ = buffer.set_hyperlink(&link).map_err(maybe_log_error!());
+                    }
+                    Message::SetHyperlink(None) => {
+                        live.linked = false;
+                        let link = HyperlinkSpec::close();
+                        let _ = buffer.set_hyperlink(&link).map_err(maybe_log_error!());
+                    }
+                    Message::ResetStyle => {
+                        live.styled = false;
                         let _ = buffer.reset().map_err(maybe_log_error!());
                     }
-                    Ok(Message::Flush) => {
+                    Message::Live(id, lines) => live.update(id, lines),
+                    Message::EndLive(id) => {
+                        let _ = live.end(id, &mut buffer).map_err(maybe_log_error!());
+                    }
+                    Message::Flush => {
+                        let _ = live.show(&mut buffer).map_err(maybe_log_error!());
                         let _ = buffer.flush().map_err(maybe_log_error!());
                         let _ = response_sender.send(Response::Flushed);
                     }
-                    Ok(Message::Exit) | Err(_) => {
+                    Message::Exit => {
                         break;
                     }
                 };
+
+                let _ = live.show(&mut buffer).map_err(maybe_log_error!());
             }
         });
 
@@ -136,6 +655,83 @@ impl InternalFormatter {
     }
 }
 
+/// The live regions drawn below the regular output of a worker
+struct LiveRegions {
+    regions: Vec<(usize, Vec<Line>)>,
+    /// Number of lines currently drawn
+    drawn: usize,
+    /// Whether the regular output ends with a newline
+    at_line_start: bool,
+    /// Whether the regular output currently has a color set
+    styled: bool,
+    /// Whether the regular output currently is inside a hyperlink
+    linked: bool,
+}
+
+impl Default for LiveRegions {
+    fn default() -> Self {
+        LiveRegions {
+            regions: Vec::new(),
+            drawn: 0,
+            at_line_start: true,
+            styled: false,
+            linked: false,
+        }
+    }
+}
+
+impl LiveRegions {
+    fn update(&mut self, id: usize, lines: Vec<Line>) {
+        match self.regions.iter_mut().find(|(region, _)| *region == id) {
+            Some((_, region)) => *region = lines,
+            None => self.regions.push((id, lines)),
+        }
+    }
+
+    /// Move a region into the regular output
+    fn end<W: WriteColor>(&mut self, id: usize, out: &mut W) -> io::Result<()> {
+        let index = match self.regions.iter().position(|(region, _)| *region == id) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+        let (_, lines) = self.regions.remove(index);
+        if !self.at_line_start {
+            out.write_all(b"\n")?;
+        }
+        for line in &lines {
+            line.write_to(out)?;
+            out.write_all(b"\n")?;
+        }
+        self.at_line_start = true;
+        Ok(())
+    }
+
+    /// Erase all drawn lines, leaving the cursor where they started
+    fn hide<W: WriteColor>(&mut self, out: &mut W) -> io::Result<()> {
+        if self.drawn > 0 {
+            write!(out, "\x1b[{}A\x1b[J", self.drawn)?;
+            self.drawn = 0;
+        }
+        Ok(())
+    }
+
+    /// Draw all regions if they aren't already, and regular output is at a
+    /// point where they can be drawn without changing it
+    fn show<W: WriteColor>(&mut self, out: &mut W) -> io::Result<()> {
+        if self.drawn > 0 || !self.at_line_start || self.styled || self.linked {
+            return Ok(());
+        }
+        for (_, lines) in &self.regions {
+            for line in lines {
+                line.write_to(out)?;
+                out.write_all(b"\n")?;
+                self.drawn += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Drop for InternalFormatter {
     fn drop(&mut self) {
         let _ = self.sender.send(Message::Exit);
@@ -150,7 +746,12 @@ impl Drop for InternalFormatter {
 enum Message {
     Write(Vec<u8>),
     SetColor(ColorSpec),
+    SetHyperlink(Option<String>),
     ResetStyle,
+    /// Replace the lines of a live region
+    Live(usize, Vec<Line>),
+    /// Stop redrawing a live region and leave it in the regular output
+    EndLive(usize),
     Flush,
     Exit,
 }
@@ -279,3 +880,105 @@ mod test_helper {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{strip_ansi, LiveRegion};
+    use crate::components::{link, span, text};
+    use crate::test_buffer::TestBuffer;
+    use crate::{human, Error};
+    use termcolor::Buffer;
+
+    #[test]
+    fn plain_targets_never_write_escapes() -> Result<(), Error> {
+        let buffer = TestBuffer::from(Buffer::no_color());
+        let target = human::plain(buffer.clone())?.with_hyperlinks(true)?;
+        let out = crate::new().add_target(target)?;
+        out.print(span().fg("red")?.bold(true)?.add_item("error"))?;
+        out.print(link("docs", "https://example.com"))?;
+        out.flush()?;
+
+        let written = buffer.0.read().unwrap().as_slice().to_vec();
+        assert_eq!(String::from_utf8_lossy(&written), "error\ndocs\n");
+        Ok(())
+    }
+
+    #[test]
+    fn strips_ansi_sequences() {
+        assert_eq!(strip_ansi("plain"), "plain");
+        assert_eq!(strip_ansi("\x1b[38;5;10mgreen\x1b[m"), "green");
+        assert_eq!(strip_ansi("\x1b]0;title\x07text"), "text");
+        assert_eq!(strip_ansi("\x1b7saved\x1b8"), "saved");
+        assert_eq!(strip_ansi("\x1b[m\x1b(Breset"), "reset");
+        assert_eq!(strip_ansi("cut off\x1b[1"), "cut off");
+    }
+
+    #[test]
+    fn assert_live_region_is_sync_and_send() {
+        fn assert_both<T: Send + Sync>() {}
+        assert_both::<LiveRegion>();
+    }
+
+    #[test]
+    fn redraws_live_regions_below_output() -> Result<(), Error> {
+        let test_target = human::test();
+        let out = crate::new().add_target(test_target.target().with_interactive(true)?)?;
+        let region = out.live_region()?;
+        region.set(0, text("Building foo"))?;
+        out.print(text("Finished bar"))?;
+        region.set(0, text("Building baz"))?;
+        region.finish()?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "Building foo\n\
+             \x1b[1A\x1b[JFinished bar\nBuilding foo\n\
+             \x1b[1A\x1b[JBuilding baz\n\
+             \x1b[1A\x1b[JBuilding baz\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cuts_off_wide_live_lines() -> Result<(), Error> {
+        let test_target = human::test();
+        let target = test_target.target().with_interactive(true)?.with_width(5)?;
+        let out = crate::new().add_target(target)?;
+        let region = out.live_region()?;
+        region.set(1, text("Building foo"))?;
+        region.set(0, text("a\nb"))?;
+        region.remove(1)?;
+        drop(region);
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "Build\n\
+             \x1b[1A\x1b[Ja\nb\nBuild\n\
+             \x1b[3A\x1b[Ja\nb\n\
+             \x1b[2A\x1b[Ja\nb\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cuts_off_live_lines_before_wide_characters() -> Result<(), Error> {
+        let test_target = human::test();
+        let target = test_target.target().with_interactive(true)?.with_width(5)?;
+        let out = crate::new().add_target(target)?;
+        let region = out.live_region()?;
+        region.set(
+            0,
+            span()
+                .add_item("abcd")
+                .add_item(span().bold(true)?.add_item("日"))
+                .add_item(" x"),
+        )?;
+        region.finish()?;
+        out.flush()?;
+
+        assert_eq!(test_target.to_string(), "abcd\n\x1b[1A\x1b[Jabcd\n");
+        Ok(())
+    }
+}
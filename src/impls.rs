@@ -0,0 +1,517 @@
+//! `Render` implementations for types from the standard library
+
+use crate::components::list::write_item;
+use crate::{html, human, json, markdown, Error, Render};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+macro_rules! impl_render_with_display {
+    ($($t:ty)*) => {
+        $(
+            impl Render for $t {
+                fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+                    fmt.write(self.to_string())?;
+                    Ok(())
+                }
+
+                fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+                    fmt.write(self)?;
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_render_with_display!(
+    u8 u16 u32 u64 u128 usize
+    i8 i16 i32 i64 i128 isize
+    f32 f64 bool char
+);
+
+/// Render an optional value
+///
+/// `None` is rendered as a dimmed "none" for humans, and `null` in JSON.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::human;
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(Some(42))?;
+/// out.print(None::<i32>)?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "42\nnone\n");
+/// # Ok(()) }
+/// ```
+impl<T: Render> Render for Option<T> {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        match self {
+            Some(item) => item.render_for_humans(fmt),
+            None => fmt.write_styled("dim", "none"),
+        }
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        match self {
+            Some(item) => item.render_json(fmt),
+            None => fmt.write(&Value::Null),
+        }
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        match self {
+            Some(item) => item.render_html(fmt),
+            None => fmt.write_styled("dim", "none"),
+        }
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        match self {
+            Some(item) => item.render_markdown(fmt),
+            None => fmt.write_styled("dim", "none"),
+        }
+    }
+}
+
+/// Render a result
+///
+/// For humans, errors are prefixed with "error: ". In JSON, this is either
+/// `{"Ok": …}` or `{"Err": …}`, just like serde would do it.
+impl<T: Render, E: Render> Render for Result<T, E> {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        match self {
+            Ok(item) => item.render_for_humans(fmt),
+            Err(error) => {
+                fmt.write_styled("error", "error")?;
+                fmt.write(": ")?;
+                error.render_for_humans(fmt)
+            }
+        }
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        let (key, value) = match self {
+            Ok(item) => ("Ok", json::Formatter::capture(item)?),
+            Err(error) => ("Err", json::Formatter::capture(error)?),
+        };
+        fmt.write(&serde_json::json!({ key: value }))?;
+        Ok(())
+    }
+}
+
+/// Render a list of items
+///
+/// For humans, this prints each item on its own line, prefixed with a dash.
+/// In JSON, this is an array.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::human;
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// out.print(vec!["foo", "bar"])?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "- foo\n- bar\n");
+/// # Ok(()) }
+/// ```
+impl<T: Render> Render for [T] {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        for (i, item) in self.iter().enumerate() {
+            if i > 0 {
+                fmt.write("\n")?;
+            }
+            write_item(fmt, "- ", 2, item)?;
+        }
+        Ok(())
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        let values = self
+            .iter()
+            .map(json::Formatter::capture)
+            .collect::<Result<Vec<_>, _>>()?;
+        fmt.write(&values)?;
+        Ok(())
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        fmt.write_raw("<ul>")?;
+        for item in self {
+            fmt.write_raw("<li>")?;
+            item.render_html(fmt)?;
+            fmt.write_raw("</li>")?;
+        }
+        fmt.write_raw("</ul>")
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        let items = self
+            .iter()
+            .map(|item| Ok(markdown::list_item("- ", &fmt.capture(item)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        fmt.write_raw(items.join("\n"))
+    }
+}
+
+/// Render a list of items
+///
+/// Same as for slices.
+impl<T: Render> Render for Vec<T> {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        self.as_slice().render_for_humans(fmt)
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        self.as_slice().render_json(fmt)
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        self.as_slice().render_html(fmt)
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        self.as_slice().render_markdown(fmt)
+    }
+}
+
+/// Render a boxed item
+///
+/// This allows collecting items of different types, e.g. in a
+/// `Vec<Box<dyn Render>>`.
+impl<T: Render + ?Sized> Render for Box<T> {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        self.as_ref().render_for_humans(fmt)
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        self.as_ref().render_json(fmt)
+    }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        self.as_ref().render_html(fmt)
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        self.as_ref().render_markdown(fmt)
+    }
+}
+
+/// Convert a rendered value to a string usable as JSON object key
+fn json_key<K: Render + ?Sized>(key: &K) -> Result<String, Error> {
+    Ok(match json::Formatter::capture(key)? {
+        Value::String(key) => key,
+        other => other.to_string(),
+    })
+}
+
+fn render_entries_for_humans<'a, K, V, I>(
+    fmt: &mut human::Formatter,
+    entries: I,
+) -> Result<(), Error>
+where
+    K: Render + 'a,
+    V: Render + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    for (i, (key, value)) in entries.enumerate() {
+        if i > 0 {
+            fmt.write("\n")?;
+        }
+        // Values that span multiple lines are indented to line up after the key
+        let key = fmt.capture(key)?.trim_end();
+        key.render(fmt)?;
+        write_item(fmt, ": ", key.width() + 2, value)?;
+    }
+    Ok(())
+}
+
+fn render_entries_json<'a, K, V, I>(fmt: &mut json::Formatter, entries: I) -> Result<(), Error>
+where
+    K: Render + 'a,
+    V: Render + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    let mut object = serde_json::Map::new();
+    for (key, value) in entries {
+        object.insert(json_key(key)?, json::Formatter::capture(value)?);
+    }
+    fmt.write(&object)?;
+    Ok(())
+}
+
+/// Render a map
+///
+/// For humans, this prints each entry as `key: value` on its own line, sorted
+/// by key. Values that span multiple lines are indented to line up with their
+/// first line. In JSON, this is an object.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// # use convey::human;
+/// # use std::collections::HashMap;
+/// # fn main() -> Result<(), convey::Error> {
+/// # let test_target = human::test();
+/// let mut out = convey::new().add_target(test_target.target())?;
+/// let mut map = HashMap::new();
+/// map.insert("foo", 1);
+/// map.insert("bar", 2);
+/// out.print(map)?;
+/// # out.flush()?;
+/// # assert_eq!(test_target.to_string(), "bar: 2\nfoo: 1\n");
+/// # Ok(()) }
+/// ```
+impl<K, V, S> Render for HashMap<K, V, S>
+where
+    K: Render + Eq + Hash,
+    V: Render,
+    S: BuildHasher,
+{
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let mut entries = self
+            .iter()
+            .map(|(key, value)| Ok((json_key(key)?, key, value)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        render_entries_for_humans(fmt, entries.into_iter().map(|(_, k, v)| (k, v)))
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        render_entries_json(fmt, self.iter())
+    }
+}
+
+/// Render a map
+///
+/// Same as for `HashMap`, but in the map's order.
+impl<K: Render + Ord, V: Render> Render for BTreeMap<K, V> {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        render_entries_for_humans(fmt, self.iter())
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        render_entries_json(fmt, self.iter())
+    }
+}
+
+macro_rules! impl_render_for_tuple {
+    ($($name:ident . $index:tt),+) => {
+        /// Render a tuple
+        ///
+        /// For humans, this looks like `(a, b)`. In JSON, this is an array.
+        impl<$($name: Render),+> Render for ($($name,)+) {
+            fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+                let items: &[&dyn Render] = &[$(&self.$index),+];
+                fmt.write("(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        fmt.write(", ")?;
+                    }
+                    item.render_for_humans(fmt)?;
+                }
+                fmt.write(")")?;
+                Ok(())
+            }
+
+            fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+                let values = vec![$(json::Formatter::capture(&self.$index)?),+];
+                fmt.write(&values)?;
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_render_for_tuple!(A.0);
+impl_render_for_tuple!(A.0, B.1);
+impl_render_for_tuple!(A.0, B.1, C.2);
+impl_render_for_tuple!(A.0, B.1, C.2, D.3);
+impl_render_for_tuple!(A.0, B.1, C.2, D.3, E.4);
+impl_render_for_tuple!(A.0, B.1, C.2, D.3, E.4, F.5);
+impl_render_for_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6);
+impl_render_for_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7);
+
+/// Render a path
+///
+/// Paths that are not valid UTF-8 are converted lossily.
+impl Render for Path {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        fmt.write(self.display().to_string())?;
+        Ok(())
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(&self.to_string_lossy())?;
+        Ok(())
+    }
+}
+
+/// Render a path
+///
+/// Same as for `Path`.
+impl Render for PathBuf {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        self.as_path().render_for_humans(fmt)
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        self.as_path().render_json(fmt)
+    }
+}
+
+/// Render a duration
+///
+/// For humans, this looks like `1.5s` or `20ms`. In JSON, this is the number
+/// of seconds.
+impl Render for Duration {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        fmt.write(format!("{:?}", self))?;
+        Ok(())
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(&self.as_secs_f64())?;
+        Ok(())
+    }
+}
+
+/// Render a JSON value
+///
+/// Strings are shown as they are, everything else is pretty-printed JSON.
+impl Render for Value {
+    fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        match self {
+            Value::String(s) => fmt.write(s.as_str())?,
+            value => fmt.write(serde_json::to_string_pretty(value)?)?,
+        }
+        Ok(())
+    }
+
+    fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
+        fmt.write(self)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{human, json, Error, Render};
+    use std::collections::{BTreeMap, HashMap};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn render<T: Render>(item: T) -> Result<(String, String), Error> {
+        let human_output = human::test();
+        item.render_for_humans(&mut human_output.formatter())?;
+        let json_output = json::test();
+        item.render_json(&mut json_output.formatter())?;
+        Ok((human_output.to_string(), json_output.to_string()))
+    }
+
+    #[test]
+    fn renders_primitives() -> Result<(), Error> {
+        assert_eq!(render(42u8)?, ("42".into(), "42".into()));
+        assert_eq!(render(-1i64)?, ("-1".into(), "-1".into()));
+        assert_eq!(render(1.5f32)?, ("1.5".into(), "1.5".into()));
+        assert_eq!(render(true)?, ("true".into(), "true".into()));
+        assert_eq!(render('x')?, ("x".into(), "\"x\"".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn renders_options_and_results() -> Result<(), Error> {
+        assert_eq!(render(Some(1))?, ("1".into(), "1".into()));
+        assert_eq!(render(None::<u8>)?, ("none".into(), "null".into()));
+
+        let ok: Result<u8, &str> = Ok(1);
+        assert_eq!(render(ok)?, ("1".into(), "{\"Ok\":1}".into()));
+        let err: Result<u8, &str> = Err("nope");
+        assert_eq!(
+            render(err)?,
+            ("error: nope".into(), "{\"Err\":\"nope\"}".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_lists() -> Result<(), Error> {
+        assert_eq!(
+            render(vec![1, 2, 3])?,
+            ("- 1\n- 2\n- 3".into(), "[1,2,3]".into())
+        );
+        assert_eq!(
+            render(&["a", "b"][..])?,
+            ("- a\n- b".into(), "[\"a\",\"b\"]".into())
+        );
+        assert_eq!(render(Vec::<u8>::new())?, ("".into(), "[]".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn renders_maps() -> Result<(), Error> {
+        let mut map = HashMap::new();
+        map.insert("b", vec![2]);
+        map.insert("a", vec![1, 2]);
+        assert_eq!(
+            render(map)?,
+            (
+                "a: - 1\n   - 2\nb: - 2".into(),
+                "{\"a\":[1,2],\"b\":[2]}".into()
+            )
+        );
+
+        let mut map = BTreeMap::new();
+        map.insert(2, "two");
+        map.insert(1, "one");
+        assert_eq!(
+            render(map)?,
+            (
+                "1: one\n2: two".into(),
+                "{\"1\":\"one\",\"2\":\"two\"}".into()
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_tuples() -> Result<(), Error> {
+        assert_eq!(render((1,))?, ("(1)".into(), "[1]".into()));
+        assert_eq!(
+            render((1, "a", Some(true)))?,
+            ("(1, a, true)".into(), "[1,\"a\",true]".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_paths_durations_and_values() -> Result<(), Error> {
+        assert_eq!(
+            render(PathBuf::from("/tmp/foo"))?,
+            ("/tmp/foo".into(), "\"/tmp/foo\"".into())
+        );
+        assert_eq!(
+            render(Duration::from_millis(1500))?,
+            ("1.5s".into(), "1.5".into())
+        );
+        assert_eq!(
+            render(serde_json::json!({ "a": [1] }))?,
+            ("{\n  \"a\": [\n    1\n  ]\n}".into(), "{\"a\":[1]}".into())
+        );
+        assert_eq!(
+            render(serde_json::json!("hi"))?,
+            ("hi".into(), "\"hi\"".into())
+        );
+        Ok(())
+    }
+}
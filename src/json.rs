@@ -1,12 +1,13 @@
 //! JSON output
 
-use crate::{Error, Target};
+use crate::worker::Worker;
+use crate::{Error, Render, Target};
 use failure::ResultExt;
 use serde::Serialize;
-use serde_json::to_vec as write_json;
+use serde_json::{to_vec as write_json, Value};
 use std::io::Write;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// Construct a new JSON output target that writes to stdout
 pub fn stdout() -> Result<Target, Error> {
@@ -25,7 +26,6 @@ pub fn file<T: AsRef<Path>>(name: T) -> Result<Target, Error> {
 
         let target = if path.exists() {
             let mut f = OpenOptions::new()
-                .write(true)
                 .append(true)
                 .open(&path)
                 .with_context(|_| format!("Can't open file `{}` as JSON target", path.display()))?;
@@ -47,7 +47,14 @@ pub use self::test_helper::test;
 /// JSON formatter
 #[derive(Clone)]
 pub struct Formatter {
-    inner: Arc<InternalFormatter>,
+    inner: Backend,
+}
+
+#[derive(Clone)]
+enum Backend {
+    Worker(Arc<Worker>),
+    /// Collects written items instead of writing them anywhere
+    Capture(Arc<Mutex<Vec<Value>>>),
 }
 
 impl Formatter {
@@ -55,131 +62,60 @@ impl Formatter {
         init: F,
     ) -> Result<Self, Error> {
         Ok(Formatter {
-            inner: Arc::new(InternalFormatter::init_with(init)?),
+            inner: Backend::Worker(Arc::new(Worker::init_with(init)?)),
+        })
+    }
+
+    /// Render an item to JSON values instead of writing it
+    ///
+    /// This is useful for components that contain other components, e.g. to
+    /// put them into an array. Items that write multiple times (like spans)
+    /// result in an array, items that don't write anything in `null`.
+    pub(crate) fn capture<R: Render + ?Sized>(item: &R) -> Result<Value, Error> {
+        let values = Arc::new(Mutex::new(Vec::new()));
+        let mut fmt = Formatter {
+            inner: Backend::Capture(values.clone()),
+        };
+        item.render_json(&mut fmt)?;
+
+        let mut values = values.lock().map_err(|e| Error::sync_error(&e))?;
+        Ok(match values.len() {
+            0 => Value::Null,
+            1 => values.remove(0),
+            _ => Value::Array(values.drain(..).collect()),
         })
     }
 
     /// Write a serializable item to the JSON formatter
     pub fn write<T: Serialize>(&self, item: &T) -> Result<(), Error> {
-        self.send(Message::Write(write_json(item)?))?;
+        match &self.inner {
+            Backend::Worker(worker) => worker.write(write_json(item)?)?,
+            Backend::Capture(values) => {
+                let value = serde_json::to_value(item)?;
+                let mut values = values.lock().map_err(|e| Error::sync_error(&e))?;
+                values.push(value);
+            }
+        }
         Ok(())
     }
 
     /// Immediately write all buffered output
     pub fn flush(&self) -> Result<(), Error> {
-        self.send(Message::Flush)?;
-
-        match self.inner.receiver.recv() {
-            Ok(Response::Flushed) => Ok(()),
-            msg => Err(Error::worker_error(format!("unexpected message {:?}", msg))),
+        match &self.inner {
+            Backend::Worker(worker) => worker.flush(),
+            Backend::Capture(_) => Ok(()),
         }
     }
 
     /// Write a separator after a record
     pub(crate) fn write_separator(&mut self) -> Result<(), Error> {
-        self.send(Message::Write(vec![b'\n']))?;
-        Ok(())
-    }
-
-    fn send(&self, msg: Message) -> Result<(), Error> {
-        self.inner.sender.send(msg)?;
-        Ok(())
-    }
-}
-
-use crossbeam_channel as channel;
-use std::thread;
-
-struct InternalFormatter {
-    sender: channel::Sender<Message>,
-    receiver: channel::Receiver<Response>,
-    // Only an option so we can `take` this in `Drop::drop`
-    worker: Option<thread::JoinHandle<()>>,
-}
-
-impl InternalFormatter {
-    pub(crate) fn init_with<W: Write, F: FnOnce() -> Result<W, Error> + Send + 'static>(
-        init: F,
-    ) -> Result<Self, Error> {
-        let (message_sender, message_receiver) = channel::unbounded();
-        let (response_sender, response_receiver) = channel::bounded(0);
-
-        let worker = thread::spawn(move || {
-            let mut buffer = match init() {
-                Ok(buf) => {
-                    let _ = response_sender.send(Response::StartedSuccessfully);
-                    buf
-                }
-                Err(e) => {
-                    let _ = response_sender.send(Response::Error(e));
-                    return;
-                }
-            };
-
-            macro_rules! maybe_log_error {
-                () => {
-                    |e| {
-                        if cfg!(debug_assertions) {
-                            eprintln!("{}", e)
-                        } else {
-                            ()
-                        }
-                    }
-                };
-            }
-
-            loop {
-                match message_receiver.recv() {
-                    Ok(Message::Write(data)) => {
-                        let _ = buffer.write_all(&data).map_err(maybe_log_error!());
-                    }
-                    Ok(Message::Flush) => {
-                        let _ = buffer.flush().map_err(maybe_log_error!());
-                        let _ = response_sender.send(Response::Flushed);
-                    }
-                    Ok(Message::Exit) | Err(_) => {
-                        break;
-                    }
-                };
-            }
-        });
-
-        match response_receiver.recv() {
-            Ok(Response::Error(error)) => Err(error),
-            Ok(Response::StartedSuccessfully) => Ok(InternalFormatter {
-                worker: Some(worker),
-                sender: message_sender,
-                receiver: response_receiver,
-            }),
-            msg => Err(Error::worker_error(format!("unexpected message {:?}", msg))),
-        }
-    }
-}
-
-impl Drop for InternalFormatter {
-    fn drop(&mut self) {
-        let _ = self.sender.send(Message::Exit);
-        // TODO: Docs say this may panic, so have a look at how to deal with that.
-        if let Some(worker) = self.worker.take() {
-            let _ = worker.join();
+        match &self.inner {
+            Backend::Worker(worker) => worker.write(vec![b'\n']),
+            Backend::Capture(_) => Ok(()),
         }
     }
 }
 
-#[derive(Debug)]
-enum Message {
-    Write(Vec<u8>),
-    Flush,
-    Exit,
-}
-
-#[derive(Debug)]
-enum Response {
-    StartedSuccessfully,
-    Error(Error),
-    Flushed,
-}
-
 /// Shorthand for writing the `render_json` method of the `Render`  trait
 ///
 /// # Examples
@@ -212,18 +148,20 @@ enum Response {
 #[macro_export]
 macro_rules! render_json {
     () => {
-        fn render_json(&self, fmt: &mut $crate::json::Formatter) -> ::std::result::Result<(), $crate::Error> {
+        fn render_json(
+            &self,
+            fmt: &mut $crate::json::Formatter,
+        ) -> ::std::result::Result<(), $crate::Error> {
             fmt.write(self)?;
             Ok(())
         }
-    }
+    };
 }
 
 mod test_helper {
     use super::Formatter;
-    use crate::test_buffer::TestBuffer;
+    use crate::test_buffer::TestTarget;
     use crate::Target;
-    use termcolor::Buffer;
 
     /// Create a test output target
     ///
@@ -260,33 +198,8 @@ mod test_helper {
     ///     Ok(())
     /// }
     /// ```
-    pub fn test() -> TestTarget {
-        TestTarget {
-            buffer: Buffer::no_color().into(),
-        }
-    }
-
-    pub struct TestTarget {
-        buffer: TestBuffer,
-    }
-
-    impl TestTarget {
-        pub fn formatter(&self) -> Formatter {
-            let buffer = self.buffer.clone();
-            Formatter::init_with(|| Ok(buffer)).unwrap()
-        }
-
-        pub fn target(&self) -> Target {
-            Target::json(self.formatter())
-        }
-    }
-
-    impl ::std::fmt::Display for TestTarget {
-        fn fmt(&self, f: &mut ::std::fmt::Formatter) -> Result<(), ::std::fmt::Error> {
-            let target = self.buffer.0.clone();
-            let buffer = target.read().unwrap();
-            write!(f, "{}", String::from_utf8_lossy(buffer.as_slice()))
-        }
+    pub fn test() -> TestTarget<Formatter> {
+        TestTarget::new(|buffer| Formatter::init_with(|| Ok(buffer)), Target::json)
     }
 }
 
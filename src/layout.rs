@@ -0,0 +1,354 @@
+//! Building blocks for components that lay out other components
+//!
+//! Human output of an item can be captured (see `human::Formatter::capture`)
+//! and is then available as lines of styled segments. These can be measured,
+//! indented, and replayed to the actual formatter.
+
+use crate::{human, Error};
+use std::io;
+use termcolor::{ColorSpec, HyperlinkSpec, WriteColor};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Display width of some text in terminal columns
+pub(crate) fn width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Human output split into lines
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Captured {
+    lines: Vec<Line>,
+}
+
+impl Captured {
+    /// Append text, starting new lines at `\n`
+    pub(crate) fn write(&mut self, text: &str, color: Option<&ColorSpec>, link: Option<&str>) {
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 || self.lines.is_empty() {
+                self.lines.push(Line::default());
+            }
+            if let Some(line) = self.lines.last_mut() {
+                line.push(part, color, link);
+            }
+        }
+    }
+
+    /// Remove empty lines at the end, e.g. of an item that ends with a newline
+    pub(crate) fn trim_end(mut self) -> Self {
+        while self.lines.last().is_some_and(Line::is_empty) {
+            self.lines.pop();
+        }
+        self
+    }
+
+    /// Break lines that are wider than `max_width` at whitespace
+    pub(crate) fn wrap(self, max_width: usize) -> Self {
+        Captured {
+            lines: self
+                .lines
+                .iter()
+                .flat_map(|line| line.wrap(max_width))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    /// Width of the widest line
+    pub(crate) fn width(&self) -> usize {
+        self.lines.iter().map(Line::width).max().unwrap_or(0)
+    }
+
+    /// Write all lines, separated by newlines
+    pub(crate) fn render(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 {
+                fmt.write("\n")?;
+            }
+            line.render(fmt)?;
+        }
+        Ok(())
+    }
+}
+
+/// A line of styled text, without the trailing newline
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Line {
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Segment {
+    text: String,
+    color: Option<ColorSpec>,
+    link: Option<String>,
+}
+
+impl Line {
+    fn push(&mut self, text: &str, color: Option<&ColorSpec>, link: Option<&str>) {
+        if text.is_empty() {
+            return;
+        }
+        if let Some(last) = self.segments.last_mut() {
+            if last.color.as_ref() == color && last.link.as_deref() == link {
+                last.text.push_str(text);
+                return;
+            }
+        }
+        self.segments.push(Segment {
+            text: text.to_string(),
+            color: color.cloned(),
+            link: link.map(String::from),
+        });
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub(crate) fn width(&self) -> usize {
+        self.segments.iter().map(|s| width(&s.text)).sum()
+    }
+
+    /// Break the line into lines that are at most `max_width` columns wide
+    ///
+    /// Whitespace at the break is dropped. Words that are wider than
+    /// `max_width` on their own are broken anywhere.
+    pub(crate) fn wrap(&self, max_width: usize) -> Vec<Line> {
+        let max_width = max_width.max(1);
+        let mut lines = vec![Line::default()];
+        let mut current = 0;
+        let mut spaces: Vec<(&str, &Segment)> = Vec::new();
+
+        for segment in &self.segments {
+            for word in words(&segment.text) {
+                if word.starts_with(char::is_whitespace) {
+                    spaces.push((word, segment));
+                    continue;
+                }
+
+                let spaces_width: usize = spaces.iter().map(|(s, _)| width(s)).sum();
+                if current > 0 && current + spaces_width + width(word) > max_width {
+                    lines.push(Line::default());
+                    current = 0;
+                    spaces.clear();
+                }
+                for (space, segment) in spaces.drain(..) {
+                    segment.push_to(lines.last_mut(), space);
+                    current += width(space);
+                }
+
+                let mut rest = word;
+                while !rest.is_empty() && current + width(rest) > max_width {
+                    let (head, tail) = split_at_width(rest, max_width.saturating_sub(current));
+                    if head.is_empty() && current == 0 {
+                        // The character is wider than a whole line, so it has to overflow
+                        let (first, tail) =
+                            rest.split_at(rest.chars().next().map_or(0, char::len_utf8));
+                        segment.push_to(lines.last_mut(), first);
+                        current = width(first);
+                        rest = tail;
+                        continue;
+                    }
+                    segment.push_to(lines.last_mut(), head);
+                    lines.push(Line::default());
+                    current = 0;
+                    rest = tail;
+                }
+                segment.push_to(lines.last_mut(), rest);
+                current += width(rest);
+            }
+        }
+        lines
+    }
+
+    /// The text of the line with its color and hyperlink, in parts
+    pub(crate) fn segments(
+        &self,
+    ) -> impl Iterator<Item = (&str, Option<&ColorSpec>, Option<&str>)> {
+        self.segments.iter().map(|segment| {
+            (
+                segment.text.as_str(),
+                segment.color.as_ref(),
+                segment.link.as_deref(),
+            )
+        })
+    }
+
+    /// Cut off everything after the first `max_width` columns
+    pub(crate) fn truncate(&self, max_width: usize) -> Line {
+        let mut line = Line::default();
+        let mut current = 0;
+        for segment in &self.segments {
+            if current >= max_width {
+                break;
+            }
+            let (head, rest) = split_at_width(&segment.text, max_width - current);
+            segment.push_to(Some(&mut line), head);
+            current += width(head);
+            if !rest.is_empty() {
+                break;
+            }
+        }
+        line
+    }
+
+    pub(crate) fn render(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        for segment in &self.segments {
+            if let Some(link) = &segment.link {
+                fmt.set_hyperlink(Some(link))?;
+            }
+            match &segment.color {
+                Some(color) => {
+                    fmt.set_color(color)?;
+                    fmt.write(segment.text.as_str())?;
+                    fmt.reset()?;
+                }
+                None => fmt.write(segment.text.as_str())?,
+            }
+            if segment.link.is_some() {
+                fmt.set_hyperlink(None)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the line to the terminal directly, i.e. from the worker thread
+    pub(crate) fn write_to<W: WriteColor>(&self, out: &mut W) -> io::Result<()> {
+        for segment in &self.segments {
+            if let Some(link) = &segment.link {
+                out.set_hyperlink(&HyperlinkSpec::open(link.as_bytes()))?;
+            }
+            match &segment.color {
+                Some(color) => {
+                    out.set_color(color)?;
+                    out.write_all(segment.text.as_bytes())?;
+                    out.reset()?;
+                }
+                None => out.write_all(segment.text.as_bytes())?,
+            }
+            if segment.link.is_some() {
+                out.set_hyperlink(&HyperlinkSpec::close())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Segment {
+    fn push_to(&self, line: Option<&mut Line>, text: &str) {
+        if let Some(line) = line {
+            line.push(text, self.color.as_ref(), self.link.as_deref());
+        }
+    }
+}
+
+/// Split text into alternating runs of whitespace and other characters
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let end = rest
+            .find(|c: char| c.is_whitespace() != first.is_whitespace())
+            .unwrap_or(rest.len());
+        let (word, tail) = rest.split_at(end);
+        rest = tail;
+        Some(word)
+    })
+}
+
+/// Split text so that the first part is at most `max_width` columns wide
+///
+/// The first part may be narrower than `max_width`, or even empty, if the
+/// next character is a wide one that doesn't fit anymore.
+pub(crate) fn split_at_width(text: &str, max_width: usize) -> (&str, &str) {
+    let mut current = 0;
+    for (index, c) in text.char_indices() {
+        current += c.width().unwrap_or(0);
+        if current > max_width {
+            return text.split_at(index);
+        }
+    }
+    (text, "")
+}
+
+#[cfg(test)]
+mod test {
+    use super::Captured;
+    use crate::components::{span, text};
+    use crate::{human, Error};
+
+    #[test]
+    fn captures_styled_lines() -> Result<(), Error> {
+        let test_target = human::test_with_color();
+        let mut fmt = test_target.formatter();
+
+        let item = span()
+            .add_item("a\nb")
+            .add_item(span().fg("red")?.add_item("c"))
+            .add_item(text("\n"));
+        let captured = fmt.capture(&item)?;
+        assert_eq!(captured.lines().len(), 3);
+
+        let captured = captured.trim_end();
+        assert_eq!(captured.lines().len(), 2);
+
+        for line in captured.lines() {
+            line.render(&mut fmt)?;
+        }
+        fmt.flush()?;
+        assert_eq!(test_target.to_string(), "ab\u{1b}[0m\u{1b}[31mc\u{1b}[0m");
+        Ok(())
+    }
+
+    #[test]
+    fn wraps_at_whitespace() -> Result<(), Error> {
+        let fmt = human::test().formatter();
+        let plain = |captured: Captured| -> Result<Vec<String>, Error> {
+            let mut lines = Vec::new();
+            for line in captured.lines() {
+                let test_target = human::test();
+                let mut fmt = test_target.formatter();
+                line.render(&mut fmt)?;
+                fmt.flush()?;
+                lines.push(test_target.to_string());
+            }
+            Ok(lines)
+        };
+
+        let captured = fmt.capture(&text("lorem ipsum  dolor\nsit amet"))?;
+        assert_eq!(
+            plain(captured.wrap(11))?,
+            vec!["lorem ipsum", "dolor", "sit amet"]
+        );
+
+        let captured = fmt.capture(&text("abcdefgh ij"))?;
+        assert_eq!(plain(captured.wrap(3))?, vec!["abc", "def", "gh", "ij"]);
+
+        let captured = fmt.capture(&text("a日本"))?;
+        assert_eq!(plain(captured.wrap(2))?, vec!["a", "日", "本"]);
+        let captured = fmt.capture(&text("a日本"))?;
+        assert_eq!(plain(captured.wrap(1))?, vec!["a", "日", "本"]);
+        Ok(())
+    }
+
+    #[test]
+    fn truncates_before_wide_characters() -> Result<(), Error> {
+        let test_target = human::test();
+        let mut fmt = test_target.formatter();
+        let item = span()
+            .add_item("abcd")
+            .add_item(span().bold(true)?.add_item("日"))
+            .add_item(" x");
+        let captured = fmt.capture(&item)?;
+        let line = captured.lines()[0].truncate(5);
+        assert_eq!(line.width(), 4);
+
+        line.render(&mut fmt)?;
+        fmt.flush()?;
+        assert_eq!(test_target.to_string(), "abcd");
+        Ok(())
+    }
+}
@@ -14,8 +14,6 @@
 
 #![warn(missing_docs)]
 
-use failure;
-
 /// Create a new output
 pub fn new() -> Output {
     Output::default()
@@ -30,6 +28,8 @@ pub struct Output {
 #[derive(Default, Clone)]
 struct InnerOutput {
     targets: Vec<Target>,
+    /// Where prompts read answers from, stdin if not set
+    input: Option<prompt::Reader>,
     #[cfg(feature = "log")]
     log_level: Option<log::Level>,
 }
@@ -44,6 +44,18 @@ impl Output {
         Ok(self)
     }
 
+    /// Read answers to prompts from `input` instead of stdin
+    ///
+    /// This is useful for driving prompts in tests, see the
+    /// [`prompt`](prompt/index.html) module.
+    pub fn with_input<R: std::io::BufRead + Send + 'static>(self, input: R) -> Result<Self, Error> {
+        {
+            let mut o = self.inner.lock().map_err(|e| Error::sync_error(&e))?;
+            o.input = Some(Arc::new(Mutex::new(Box::new(input))));
+        }
+        Ok(self)
+    }
+
     /// Initializes the global logger with an `Output` instance with
     /// `max_log_level` set to a specific log level.
     ///
@@ -102,12 +114,150 @@ impl Target {
             inner: InnerTarget::Json(Arc::new(Mutex::new(f))),
         }
     }
+
+    /// HTML output
+    ///
+    /// For reports that are read in a browser.
+    pub(crate) fn html(f: html::Formatter) -> Self {
+        Target {
+            inner: InnerTarget::Html(Arc::new(Mutex::new(f))),
+        }
+    }
+
+    /// Markdown output
+    ///
+    /// For comments on pull requests and documentation.
+    pub(crate) fn markdown(f: markdown::Formatter) -> Self {
+        Target {
+            inner: InnerTarget::Markdown(Arc::new(Mutex::new(f))),
+        }
+    }
+
+    /// Use a theme to look up named styles
+    ///
+    /// This affects human, HTML and Markdown targets.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate convey;
+    /// use convey::{human, theme::Theme};
+    ///
+    /// # fn main() -> Result<(), convey::Error> {
+    /// let out = convey::new().add_target(human::stdout()?.with_theme(Theme::light())?)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn with_theme(self, theme: theme::Theme) -> Result<Self, Error> {
+        match &self.inner {
+            InnerTarget::Human(fmt) => {
+                let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+                fmt.set_theme(theme);
+            }
+            InnerTarget::Html(fmt) => {
+                let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+                fmt.set_theme(theme);
+            }
+            InnerTarget::Markdown(fmt) => {
+                let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+                fmt.set_theme(theme);
+            }
+            InnerTarget::Json(_) => {}
+        }
+        Ok(self)
+    }
+
+    /// Override which colors the target is able to display
+    ///
+    /// This only affects human targets. By default, this is detected from the
+    /// environment.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate convey;
+    /// use convey::{color::ColorSupport, human};
+    ///
+    /// # fn main() -> Result<(), convey::Error> {
+    /// let target = human::stdout()?.with_color_support(ColorSupport::Ansi256)?;
+    /// let out = convey::new().add_target(target)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn with_color_support(self, support: color::ColorSupport) -> Result<Self, Error> {
+        if let InnerTarget::Human(fmt) = &self.inner {
+            let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+            fmt.set_color_support(support);
+        }
+        Ok(self)
+    }
+
+    /// Override which characters the target is able to display
+    ///
+    /// This only affects human targets. By default, this is detected from the
+    /// environment.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate convey;
+    /// use convey::{glyphs::UnicodeSupport, human};
+    ///
+    /// # fn main() -> Result<(), convey::Error> {
+    /// let target = human::stdout()?.with_unicode_support(UnicodeSupport::Ascii)?;
+    /// let out = convey::new().add_target(target)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn with_unicode_support(self, support: glyphs::UnicodeSupport) -> Result<Self, Error> {
+        if let InnerTarget::Human(fmt) = &self.inner {
+            let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+            fmt.set_unicode_support(support);
+        }
+        Ok(self)
+    }
+
+    /// Override whether the target is able to display hyperlinks
+    ///
+    /// This only affects human targets. By default, this is detected from the
+    /// environment.
+    pub fn with_hyperlinks(self, yes: bool) -> Result<Self, Error> {
+        if let InnerTarget::Human(fmt) = &self.inner {
+            let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+            fmt.set_hyperlinks(yes);
+        }
+        Ok(self)
+    }
+
+    /// Override whether the target is a terminal that output can be redrawn on
+    ///
+    /// This only affects human targets and decides how
+    /// [live regions](human/struct.LiveRegion.html) are shown. By default,
+    /// this is detected from the environment.
+    pub fn with_interactive(self, yes: bool) -> Result<Self, Error> {
+        if let InnerTarget::Human(fmt) = &self.inner {
+            let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+            fmt.set_interactive(yes);
+        }
+        Ok(self)
+    }
+
+    /// Set the number of columns that output is laid out in
+    ///
+    /// This only affects human targets. By default, this is the width of the
+    /// terminal, or unlimited if it can't be detected.
+    pub fn with_width(self, columns: usize) -> Result<Self, Error> {
+        if let InnerTarget::Human(fmt) = &self.inner {
+            let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+            fmt.set_width(Some(columns));
+        }
+        Ok(self)
+    }
 }
 
 #[derive(Clone)]
 enum InnerTarget {
     Human(Arc<Mutex<human::Formatter>>),
     Json(Arc<Mutex<json::Formatter>>),
+    Html(Arc<Mutex<html::Formatter>>),
+    Markdown(Arc<Mutex<markdown::Formatter>>),
 }
 
 mod error;
@@ -121,20 +271,75 @@ impl Output {
             match &target.inner {
                 InnerTarget::Human(fmt) => {
                     let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
-                    item.render_for_humans(&mut *fmt)?;
+                    item.render_for_humans(&mut fmt)?;
                     fmt.write("\n")?;
                 }
                 InnerTarget::Json(fmt) => {
                     let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
-                    item.render_json(&mut *fmt)?;
+                    item.render_json(&mut fmt)?;
                     fmt.write_separator()?;
                 }
+                InnerTarget::Html(fmt) => {
+                    let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+                    fmt.write_item(&item)?;
+                }
+                InnerTarget::Markdown(fmt) => {
+                    let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+                    fmt.write_item(&item)?;
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Create lines at the bottom of human targets that are updated in place
+    ///
+    /// See [`LiveRegion`](human/struct.LiveRegion.html). Other targets don't
+    /// show live regions, print the final state of your items to them instead.
+    pub fn live_region(&self) -> Result<human::LiveRegion, Error> {
+        let o = self.inner.lock().map_err(|e| Error::sync_error(&e))?;
+        let formatters = o
+            .targets
+            .iter()
+            .filter_map(|target| match &target.inner {
+                InnerTarget::Human(fmt) => Some(fmt.clone()),
+                InnerTarget::Json(_) | InnerTarget::Html(_) | InnerTarget::Markdown(_) => None,
+            })
+            .collect();
+        Ok(human::LiveRegion::new(formatters))
+    }
+
+    /// Ask a question and wait for the answer
+    ///
+    /// See the [`prompt`](prompt/index.html) module for how humans and
+    /// machines are asked.
+    pub fn prompt<P: prompt::Prompt>(&self, prompt: P) -> Result<P::Answer, Error> {
+        // Don't hold the lock while waiting for the answer, so other threads
+        // can keep printing
+        let mut humans = Vec::new();
+        let mut machines = Vec::new();
+        let input = {
+            let o = self.inner.lock().map_err(|e| Error::sync_error(&e))?;
+            for target in &o.targets {
+                match &target.inner {
+                    InnerTarget::Human(fmt) => humans.push(fmt.clone()),
+                    InnerTarget::Json(fmt) => machines.push(fmt.clone()),
+                    InnerTarget::Html(_) | InnerTarget::Markdown(_) => {}
+                }
+            }
+            o.input.clone()
+        };
+
+        match &input {
+            Some(input) => {
+                let mut input = input.lock().map_err(|e| Error::sync_error(&e))?;
+                prompt::ask(&prompt, &humans, &machines, &mut *input)
+            }
+            None => prompt::ask(&prompt, &humans, &machines, &mut std::io::stdin().lock()),
+        }
+    }
+
     /// Immediately write all buffered output
     pub fn flush(&self) -> Result<(), Error> {
         let o = self.inner.lock().map_err(|e| Error::sync_error(&e))?;
@@ -148,6 +353,14 @@ impl Output {
                     let fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
                     fmt.flush()?;
                 }
+                InnerTarget::Html(fmt) => {
+                    let fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+                    fmt.flush()?;
+                }
+                InnerTarget::Markdown(fmt) => {
+                    let fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+                    fmt.flush()?;
+                }
             }
         }
 
@@ -165,6 +378,20 @@ pub trait Render {
     /// `fmt.write(self)`. Alternatively, you might want to use something like
     /// serde_json's `json!` macro.
     fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error>;
+    /// How to render your type to HTML
+    ///
+    /// By default, this is the human output with its styling, see
+    /// [`html::Formatter::write_human`](html/struct.Formatter.html#method.write_human).
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        fmt.write_human(self)
+    }
+    /// How to render your type to Markdown
+    ///
+    /// By default, this is the human output with styles mapped to emphasis,
+    /// see [`markdown::Formatter::write_human`](markdown/struct.Formatter.html#method.write_human).
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        fmt.write_human(self)
+    }
 }
 
 /// Render automatically works with references
@@ -183,9 +410,9 @@ pub trait Render {
 /// # assert_eq!(test_target.to_string(), "owned element\nreference to an element\n");
 /// # Ok(()) }
 /// ```
-impl<'a, T> Render for &'a T
+impl<T> Render for &T
 where
-    T: Render,
+    T: Render + ?Sized,
 {
     fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
         (*self).render_for_humans(fmt)
@@ -194,6 +421,14 @@ where
     fn render_json(&self, fmt: &mut json::Formatter) -> Result<(), Error> {
         (*self).render_json(fmt)
     }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        (*self).render_html(fmt)
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        (*self).render_markdown(fmt)
+    }
 }
 
 /// Render a string slice
@@ -211,7 +446,7 @@ where
 /// # assert_eq!(test_target.to_string(), "Hello, World!\n");
 /// # Ok(()) }
 /// ```
-impl<'a> Render for &'a str {
+impl Render for &str {
     fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
         fmt.write(self.as_bytes())?;
         Ok(())
@@ -221,6 +456,14 @@ impl<'a> Render for &'a str {
         fmt.write(&self)?;
         Ok(())
     }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        fmt.write(self)
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        fmt.write(self)
+    }
 }
 
 /// Render a string
@@ -238,7 +481,7 @@ impl<'a> Render for &'a str {
 /// # assert_eq!(test_target.to_string(), "Hello, World!\n");
 /// # Ok(()) }
 /// ```
-impl<'a> Render for String {
+impl Render for String {
     fn render_for_humans(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
         fmt.write(self.as_bytes())?;
         Ok(())
@@ -248,11 +491,29 @@ impl<'a> Render for String {
         fmt.write(&self)?;
         Ok(())
     }
+
+    fn render_html(&self, fmt: &mut html::Formatter) -> Result<(), Error> {
+        fmt.write(self)
+    }
+
+    fn render_markdown(&self, fmt: &mut markdown::Formatter) -> Result<(), Error> {
+        fmt.write(self)
+    }
 }
 
+pub mod color;
 pub mod components;
+pub mod glyphs;
+pub mod html;
 pub mod human;
+mod impls;
 pub mod json;
+mod layout;
+pub mod markdown;
+mod pager;
+pub mod prompt;
+pub mod theme;
+mod worker;
 
 #[cfg(feature = "log")]
 mod logging;
@@ -12,11 +12,11 @@ pub struct LogMessage {
 impl crate::Render for LogMessage {
     crate::render_for_humans!(self -> [
         match self.level {
-            Level::Error => span!(fg = "red", [text("ERROR"),]),
-            Level::Warn => span!(fg = "yellow", [text("WARN "),]),
-            Level::Info => span!(fg = "blue", [text("INFO "),]),
-            Level::Debug => span!(fg = "cyan", [text("DEBUG "),]),
-            Level::Trace => span!(fg = "white", [text("DEBUG  "),]),
+            Level::Error => span!(style = "level.error", [text("ERROR"),]),
+            Level::Warn => span!(style = "level.warn", [text("WARN "),]),
+            Level::Info => span!(style = "level.info", [text("INFO "),]),
+            Level::Debug => span!(style = "level.debug", [text("DEBUG "),]),
+            Level::Trace => span!(style = "level.trace", [text("DEBUG  "),]),
         },
         if self.path.is_empty() {
             span!([text(": "),])
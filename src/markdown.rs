@@ -0,0 +1,509 @@
+//! Markdown output
+//!
+//! Items are rendered with [`Render::render_markdown`], as GitHub flavored
+//! Markdown that can be posted as a comment on a pull request or included in
+//! documentation. By default, that is an item's human output: single lines
+//! become text, with bold, italic and strikethrough styles mapped to
+//! emphasis, and anything longer becomes a code block. Colors are dropped.
+//! Components that have a Markdown equivalent render to that instead:
+//! headings, lists, links, code blocks, and tables for key-value pairs and
+//! columns.
+//!
+//! Items are separated by blank lines, so each one is a block of its own.
+//!
+//! [`Render::render_markdown`]: ../trait.Render.html#method.render_markdown
+
+use crate::theme::Theme;
+use crate::worker::{Backend, Worker};
+use crate::{human, Error, Render, Target};
+use failure::ResultExt;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use termcolor::ColorSpec;
+
+/// Create a new Markdown output that writes to stdout
+pub fn stdout() -> Result<Target, Error> {
+    let formatter = Formatter::init_with(|| Ok(io::stdout()))?;
+    Ok(Target::markdown(formatter))
+}
+
+/// Create a new Markdown output that writes to a file
+///
+/// An existing file is overwritten.
+pub fn file<T: AsRef<Path>>(name: T) -> Result<Target, Error> {
+    let path = name.as_ref().to_path_buf();
+    let formatter = Formatter::init_with(move || {
+        use std::fs::File;
+        use std::io::BufWriter;
+
+        let file = File::create(&path)
+            .with_context(|_| format!("Can't open file `{}` as Markdown target", path.display()))?;
+        Ok(BufWriter::new(file))
+    })?;
+    Ok(Target::markdown(formatter))
+}
+
+pub use self::test_helper::test;
+
+/// Markdown formatter
+pub struct Formatter {
+    inner: Backend,
+    theme: Arc<Theme>,
+    /// Whether output has to fit into a single line, e.g. in a table cell
+    inline: bool,
+    /// Whether an item was written, so the next one needs a blank line
+    started: bool,
+}
+
+impl Formatter {
+    pub(crate) fn init_with<W: Write, F: FnOnce() -> Result<W, Error> + Send + 'static>(
+        init: F,
+    ) -> Result<Self, Error> {
+        Ok(Formatter {
+            inner: Backend::Worker(Arc::new(Worker::init_with(init)?)),
+            theme: Arc::new(Theme::default()),
+            inline: false,
+            started: false,
+        })
+    }
+
+    /// The theme used to look up named styles
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub(crate) fn set_theme(&mut self, theme: Theme) {
+        self.theme = Arc::new(theme);
+    }
+
+    /// Whether output has to fit into a single line
+    ///
+    /// This is the case in table cells. Line breaks are replaced with `<br>`
+    /// there, and blocks like code blocks aren't possible.
+    pub fn inline(&self) -> bool {
+        self.inline
+    }
+
+    /// Write text, escaping characters that have a meaning in Markdown
+    pub fn write<T: AsRef<str>>(&self, text: T) -> Result<(), Error> {
+        self.write_raw(escape(text.as_ref()))
+    }
+
+    /// Write Markdown as it is
+    pub fn write_raw<T: Into<String>>(&self, markdown: T) -> Result<(), Error> {
+        self.inner.write(markdown.into())
+    }
+
+    /// Write text with a named style from the theme
+    ///
+    /// See [`emphasize`](fn.emphasize.html) for how styles are mapped.
+    pub fn write_styled<T: AsRef<str>>(&self, style: &str, text: T) -> Result<(), Error> {
+        self.write_raw(self.styled(style, text))
+    }
+
+    /// Escaped text with a named style from the theme, without writing it
+    pub fn styled<T: AsRef<str>>(&self, style: &str, text: T) -> String {
+        let text = escape(text.as_ref());
+        match self.theme.get(style) {
+            Some(style) => emphasize(&style.to_color_spec(), &text),
+            None => text,
+        }
+    }
+
+    /// Write the human output of an item
+    ///
+    /// This is what [`Render::render_markdown`] does by default. A single
+    /// line is written as text, with emphasis for its styles and links for
+    /// its hyperlinks. Output that spans multiple lines is put into a code
+    /// block, so it keeps its alignment, or into lines separated by `<br>` if
+    /// the output has to be [`inline`](#method.inline).
+    ///
+    /// [`Render::render_markdown`]: ../trait.Render.html#method.render_markdown
+    pub fn write_human<R: Render + ?Sized>(&self, item: &R) -> Result<(), Error> {
+        let captured = human::Formatter::detached(self.theme.clone())
+            .capture(item)?
+            .trim_end();
+
+        if captured.lines().len() > 1 && !self.inline {
+            let source = captured
+                .lines()
+                .iter()
+                .map(|line| line.segments().map(|(text, _, _)| text).collect())
+                .collect::<Vec<String>>()
+                .join("\n");
+            return self.write_raw(code_block("", &source));
+        }
+
+        let lines = captured
+            .lines()
+            .iter()
+            .map(|line| {
+                line.segments()
+                    .map(|(text, color, link)| {
+                        let mut text = escape(text);
+                        if let Some(color) = color {
+                            text = emphasize(color, &text);
+                        }
+                        if let Some(link) = link {
+                            text = format!("[{}]({})", text, escape_url(link));
+                        }
+                        text
+                    })
+                    .collect()
+            })
+            .collect::<Vec<String>>();
+        self.write_raw(lines.join("<br>"))
+    }
+
+    /// Render an item to a string instead of writing it
+    ///
+    /// This is useful for components that need to post-process the Markdown
+    /// of their children, e.g. to indent it.
+    pub fn capture<R: Render + ?Sized>(&self, item: &R) -> Result<String, Error> {
+        self.capture_with(self.inline, item)
+    }
+
+    /// Render an item to a string that fits into a table cell
+    pub fn capture_inline<R: Render + ?Sized>(&self, item: &R) -> Result<String, Error> {
+        let captured = self.capture_with(true, item)?;
+        Ok(captured
+            .trim()
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("<br>"))
+    }
+
+    fn capture_with<R: Render + ?Sized>(&self, inline: bool, item: &R) -> Result<String, Error> {
+        let captured = Arc::new(Mutex::new(String::new()));
+        let mut fmt = Formatter {
+            inner: Backend::Capture(captured.clone()),
+            theme: self.theme.clone(),
+            inline,
+            started: false,
+        };
+        item.render_markdown(&mut fmt)?;
+
+        let captured = captured.lock().map_err(|e| Error::sync_error(&e))?;
+        Ok(captured.clone())
+    }
+
+    /// Write an item as a block of the document
+    pub(crate) fn write_item<R: Render + ?Sized>(&mut self, item: &R) -> Result<(), Error> {
+        if self.started {
+            self.write_raw("\n")?;
+        }
+        self.started = true;
+        item.render_markdown(self)?;
+        self.write_raw("\n")
+    }
+
+    /// Immediately write all buffered output
+    pub fn flush(&self) -> Result<(), Error> {
+        self.inner.flush()
+    }
+}
+
+/// Escape characters that have a meaning in Markdown
+///
+/// Characters that only start a block at the beginning of a line, like `#`
+/// or `-`, are only escaped there. Lines indented by four or more spaces
+/// would be a code block, so their first space is written as `&#32;`.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let mut line = line;
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        if indent >= 4 || line.starts_with('\t') {
+            escaped.push_str(if line.starts_with('\t') {
+                "&#9;"
+            } else {
+                "&#32;"
+            });
+            line = &line[1..];
+        }
+        let mut line_start = true;
+        let mut digits = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            // Only `1. ` at the start of a line begins an ordered list
+            let ends_marker = matches!(chars.peek(), None | Some(' ' | '\t' | '\n' | '\r'));
+            match c {
+                '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '~' | '&' => {
+                    escaped.push('\\')
+                }
+                '#' | '+' | '-' | '=' if line_start => escaped.push('\\'),
+                '.' | ')' if digits && ends_marker => escaped.push('\\'),
+                _ => {}
+            }
+            escaped.push(c);
+
+            digits = c.is_ascii_digit() && (line_start || digits);
+            line_start = line_start && c.is_whitespace();
+        }
+    }
+    escaped
+}
+
+/// Wrap Markdown in the emphasis for a color spec
+///
+/// Bold text becomes `**strong**`, italic text `*emphasized*`, and
+/// strikethrough text `~~deleted~~`. Colors and other attributes are
+/// dropped, since Markdown has no way to show them.
+///
+/// Emphasis can't span multiple paragraphs, so each line is emphasized on its
+/// own. Code blocks are left alone.
+pub fn emphasize(spec: &ColorSpec, markdown: &str) -> String {
+    let mut open = String::new();
+    if spec.strikethrough() {
+        open.push_str("~~");
+    }
+    if spec.bold() {
+        open.push_str("**");
+    }
+    if spec.italic() {
+        open.push('*');
+    }
+
+    if open.is_empty() {
+        return markdown.to_string();
+    }
+    let close = open.chars().rev().collect::<String>();
+
+    let mut emphasized = String::with_capacity(markdown.len());
+    // Length of the fence of the code block we're in
+    let mut fence = None;
+    for line in markdown.split_inclusive('\n') {
+        // Emphasis only works when the delimiters touch the text
+        let content = line.trim();
+        let ticks = content.len() - content.trim_start_matches('`').len();
+        match fence {
+            Some(length) if ticks >= length && ticks == content.len() => fence = None,
+            None if ticks >= 3 => fence = Some(ticks),
+            _ => {}
+        }
+        if fence.is_some() || ticks >= 3 || content.is_empty() {
+            emphasized.push_str(line);
+            continue;
+        }
+        let start = line.len() - line.trim_start().len();
+        let end = start + content.len();
+        emphasized.push_str(&line[..start]);
+        emphasized.push_str(&open);
+        emphasized.push_str(content);
+        emphasized.push_str(&close);
+        emphasized.push_str(&line[end..]);
+    }
+    emphasized
+}
+
+/// A fenced code block, with a fence that doesn't occur in `source`
+pub(crate) fn code_block(language: &str, source: &str) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in source.chars() {
+        current = if c == '`' { current + 1 } else { 0 };
+        longest = longest.max(current);
+    }
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!(
+        "{}{}\n{}\n{}",
+        fence,
+        language,
+        source.trim_end_matches('\n'),
+        fence
+    )
+}
+
+/// A list item, with the lines after the first one indented to line up with
+/// its text
+pub(crate) fn list_item(marker: &str, markdown: &str) -> String {
+    let indent = " ".repeat(marker.len());
+    let mut item = String::from(marker);
+    for (i, line) in markdown.trim_end().lines().enumerate() {
+        if i > 0 {
+            item.push('\n');
+            if !line.is_empty() {
+                item.push_str(&indent);
+            }
+        }
+        item.push_str(line);
+    }
+    item
+}
+
+/// A GitHub flavored Markdown table
+///
+/// Cells are expected to be inline already, see
+/// [`Formatter::capture_inline`](struct.Formatter.html#method.capture_inline).
+pub(crate) fn table(header: &[String], rows: &[Vec<String>]) -> String {
+    let row = |cells: &[String]| format!("| {} |", cells.join(" | "));
+    let mut table = row(header);
+    table.push('\n');
+    table.push_str(&row(&vec!["---".to_string(); header.len()]));
+    for cells in rows {
+        table.push('\n');
+        table.push_str(&row(cells));
+    }
+    table
+}
+
+/// Make a URL usable as a link destination
+pub(crate) fn escape_url(url: &str) -> String {
+    url.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+        .replace('<', "%3C")
+        .replace('>', "%3E")
+}
+
+mod test_helper {
+    use super::Formatter;
+    use crate::test_buffer::TestTarget;
+    use crate::Target;
+
+    /// Create a test output target
+    ///
+    /// Like [`json::test`](../json/fn.test.html), this is meant for tests and
+    /// panics instead of returning errors.
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// extern crate convey;
+    ///
+    /// fn main() -> Result<(), convey::Error> {
+    ///     let test_target = convey::markdown::test();
+    ///     let mut out = convey::new().add_target(test_target.target())?;
+    ///     out.print("# not a heading")?;
+    ///     out.print("*not emphasized*")?;
+    ///     out.flush()?;
+    ///
+    ///     assert_eq!(
+    ///         test_target.to_string(),
+    ///         "\\# not a heading\n\n\\*not emphasized\\*\n"
+    ///     );
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn test() -> TestTarget<Formatter> {
+        TestTarget::new(
+            |buffer| Formatter::init_with(|| Ok(buffer)),
+            Target::markdown,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{emphasize, escape};
+    use crate::components::{columns, kv, link, list, ordered_list, section, span, text, tree};
+    use crate::markdown;
+    use termcolor::ColorSpec;
+
+    type Res = Result<(), ::failure::Error>;
+
+    #[test]
+    fn escapes_markdown_syntax() {
+        assert_eq!(
+            escape("a_b *c* [d](e) <f>"),
+            "a\\_b \\*c\\* \\[d\\](e) \\<f\\>"
+        );
+        assert_eq!(
+            escape("- no\n+ list\n1. here"),
+            "\\- no\n\\+ list\n1\\. here"
+        );
+        assert_eq!(escape("1 - 2 = -1. #3"), "1 - 2 = -1. #3");
+        assert_eq!(
+            escape("10.5 MiB\n2) b\n3.\nx 4. y"),
+            "10.5 MiB\n2\\) b\n3\\.\nx 4. y"
+        );
+        assert_eq!(escape("a\n\n    b\n\tc"), "a\n\n&#32;   b\n&#9;c");
+    }
+
+    #[test]
+    fn emphasizes_each_line() -> Res {
+        let test_target = markdown::test();
+        let out = crate::new().add_target(test_target.target())?;
+        out.print(span().bold(true)?.add_item(text("one\n\ntwo")))?;
+        out.flush()?;
+
+        assert_eq!(test_target.to_string(), "**one**  \n  \n**two**\n");
+        assert_eq!(
+            emphasize(
+                &ColorSpec::new().set_italic(true).clone(),
+                "a\n```\n*b*\n```\n"
+            ),
+            "*a*\n```\n*b*\n```\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn maps_styles_to_emphasis() -> Res {
+        let test_target = markdown::test();
+        let out = crate::new().add_target(test_target.target())?;
+        out.print(
+            span()
+                .add_item("plain ")
+                .add_item(span().style("error")?.add_item("error "))
+                .add_item(span().fg("green")?.italic(true)?.add_item("good")),
+        )?;
+        out.print(span().fg("red")?.add_item("only color"))?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "plain **error** *good*\n\nonly color\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_components_to_markdown() -> Res {
+        let test_target = markdown::test();
+        let out = crate::new().add_target(test_target.target())?;
+        out.print(section(text("Results"), text("All passed.")).level(2))?;
+        out.print(
+            list()
+                .item(link("docs (new)", "https://example.com/a b"))
+                .item(text("two\nlines"))
+                .nested(ordered_list().start(9).item("x").item("y")),
+        )?;
+        out.print(kv().entry("name", "a|b").entry("tags", vec!["x", "y"]))?;
+        out.print(columns(vec![text("left"), text("right")]))?;
+        out.print(tree(text("root")).leaf(text("leaf")))?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "## Results\n\nAll passed.\n\n\
+             - [docs (new)](https://example.com/a%20b)\n\
+             - two  \n  lines\n\n  \
+             9. x\n  10. y\n\n\
+             |  |  |\n| --- | --- |\n| name | a\\|b |\n| tags | - x<br>- y |\n\n\
+             |  |  |\n| --- | --- |\n| left | right |\n\n\
+             ```\nroot\n└── leaf\n```\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "syntax-highlighting")]
+    fn renders_code_blocks() -> Res {
+        use crate::components::{code, heading};
+
+        let test_target = markdown::test();
+        let out = crate::new().add_target(test_target.target())?;
+        out.print(heading(1, text("Code")))?;
+        out.print(code("let s = \"```\";\n", "rust"))?;
+        out.flush()?;
+
+        assert_eq!(
+            test_target.to_string(),
+            "# Code\n\n````rust\nlet s = \"```\";\n````\n"
+        );
+        Ok(())
+    }
+}
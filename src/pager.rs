@@ -0,0 +1,260 @@
+//! Showing long output in a pager
+//!
+//! The pager is the writer of a human formatter's worker thread, so it sees
+//! all output in order and can wait for the pager process when flushed.
+
+use std::env;
+use std::io::{self, Write};
+use std::process::{Child, Command, Stdio};
+use termcolor::{Buffer, ColorSpec, HyperlinkSpec, WriteColor};
+use unicode_width::UnicodeWidthChar;
+
+/// The pager to use, from `$PAGER` or `less` by default
+///
+/// Like git, this runs `$PAGER` with the shell, so it can contain arguments
+/// and quotes, and sets `LESS=FRX` unless `LESS` is already set. Returns
+/// `None` if `$PAGER` is set but empty, which disables paging.
+pub(crate) fn command() -> Option<Command> {
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    shell_command(&pager, env::var_os("LESS").is_some())
+}
+
+fn shell_command(pager: &str, less_is_set: bool) -> Option<Command> {
+    if pager.trim().is_empty() {
+        return None;
+    }
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(pager);
+    if !less_is_set {
+        command.env("LESS", "FRX");
+    }
+    Some(command)
+}
+
+/// Buffers output and pipes it to a pager once it is taller than the terminal
+///
+/// Output that fits is written to `out` on flush. After a flush, the pager is
+/// waited for and output is buffered again.
+pub(crate) struct Pager<W: Write> {
+    command: Command,
+    width: usize,
+    height: usize,
+    buffer: Buffer,
+    /// Number of rows on screen since the last flush, counting wrapped lines
+    lines: usize,
+    /// Width of the current row so far
+    column: usize,
+    state: State,
+    out: W,
+}
+
+enum State {
+    Buffering,
+    Paging(Child),
+    /// The pager couldn't be started, so output is written directly
+    Failed,
+}
+
+impl<W: Write> Pager<W> {
+    pub(crate) fn new(command: Command, width: usize, height: usize, colors: bool, out: W) -> Self {
+        Pager {
+            command,
+            width: width.max(1),
+            height,
+            buffer: if colors {
+                Buffer::ansi()
+            } else {
+                Buffer::no_color()
+            },
+            lines: 0,
+            column: 0,
+            state: State::Buffering,
+            out,
+        }
+    }
+
+    /// Count the rows text takes up, with lines wrapping at the terminal's
+    /// width
+    fn count_rows(&mut self, text: &str) {
+        for c in text.chars() {
+            if c == '\n' {
+                self.lines += 1;
+                self.column = 0;
+                continue;
+            }
+            let width = c.width().unwrap_or(0);
+            if self.column + width > self.width {
+                self.lines += 1;
+                self.column = 0;
+            }
+            self.column += width;
+        }
+    }
+
+    /// Start the pager if necessary and pass the buffer on
+    fn forward(&mut self) -> io::Result<()> {
+        if let State::Buffering = self.state {
+            if self.lines < self.height {
+                return Ok(());
+            }
+            self.state = self
+                .command
+                .stdin(Stdio::piped())
+                .spawn()
+                .map_or(State::Failed, State::Paging);
+        }
+
+        match &mut self.state {
+            State::Paging(child) => {
+                if let Some(stdin) = child.stdin.as_mut() {
+                    match stdin.write_all(self.buffer.as_slice()) {
+                        // The user quit the pager, they don't want to see more
+                        Err(ref e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+                        result => result?,
+                    }
+                }
+            }
+            State::Failed => self.out.write_all(self.buffer.as_slice())?,
+            State::Buffering => {}
+        }
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Pager<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.write_all(buf)?;
+        self.count_rows(&String::from_utf8_lossy(buf));
+        self.forward()?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.forward()?;
+        match std::mem::replace(&mut self.state, State::Buffering) {
+            State::Buffering => {
+                self.out.write_all(self.buffer.as_slice())?;
+                self.buffer.clear();
+            }
+            State::Paging(mut child) => {
+                drop(child.stdin.take());
+                child.wait()?;
+            }
+            State::Failed => {}
+        }
+        self.lines = 0;
+        self.column = 0;
+        self.out.flush()
+    }
+}
+
+impl<W: Write> WriteColor for Pager<W> {
+    fn supports_color(&self) -> bool {
+        self.buffer.supports_color()
+    }
+
+    fn supports_hyperlinks(&self) -> bool {
+        self.buffer.supports_hyperlinks()
+    }
+
+    fn set_color(&mut self, spec: &ColorSpec) -> io::Result<()> {
+        self.buffer.set_color(spec)
+    }
+
+    fn set_hyperlink(&mut self, link: &HyperlinkSpec) -> io::Result<()> {
+        self.buffer.set_hyperlink(link)
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        self.buffer.reset()
+    }
+}
+
+impl<W: Write> Drop for Pager<W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::{shell_command, Pager};
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::process::Command;
+
+    type Res = Result<(), ::failure::Error>;
+
+    fn cat_to(path: &std::path::Path) -> Result<Command, ::failure::Error> {
+        let mut command = Command::new("cat");
+        command.stdout(File::create(path)?);
+        Ok(command)
+    }
+
+    #[test]
+    fn writes_short_output_directly() -> Res {
+        let dir = TempDir::new()?;
+        let paged = dir.child("paged");
+        let mut out = Vec::new();
+        {
+            let mut pager = Pager::new(cat_to(paged.path())?, 80, 3, false, &mut out);
+            pager.write_all(b"a\nb\n")?;
+            pager.flush()?;
+        }
+
+        assert_eq!(out, b"a\nb\n");
+        assert_eq!(fs::read_to_string(paged.path())?, "");
+        Ok(())
+    }
+
+    #[test]
+    fn pages_long_output() -> Res {
+        let dir = TempDir::new()?;
+        let paged = dir.child("paged");
+        let mut out = Vec::new();
+        {
+            let mut pager = Pager::new(cat_to(paged.path())?, 80, 2, false, &mut out);
+            pager.write_all(b"a\nb\n")?;
+            pager.write_all(b"c\n")?;
+            pager.flush()?;
+        }
+
+        assert_eq!(out, b"");
+        assert_eq!(fs::read_to_string(paged.path())?, "a\nb\nc\n");
+        Ok(())
+    }
+
+    #[test]
+    fn counts_wrapped_lines() -> Res {
+        let dir = TempDir::new()?;
+        let paged = dir.child("paged");
+        let mut out = Vec::new();
+        {
+            let mut pager = Pager::new(cat_to(paged.path())?, 4, 3, false, &mut out);
+            pager.write_all("abcdef\n日本語\n".as_bytes())?;
+            pager.flush()?;
+        }
+
+        assert_eq!(out, b"");
+        assert_eq!(fs::read_to_string(paged.path())?, "abcdef\n日本語\n");
+        Ok(())
+    }
+
+    #[test]
+    fn runs_the_pager_with_the_shell() -> Res {
+        let dir = TempDir::new()?;
+        let paged = dir.child("paged");
+        let mut command = shell_command("printf '%s %s' \"$LESS\" 'quoted arg'", false).unwrap();
+        command.stdout(File::create(paged.path())?);
+        command.status()?;
+        assert_eq!(fs::read_to_string(paged.path())?, "FRX quoted arg");
+
+        let command = shell_command("less", true).unwrap();
+        assert_eq!(command.get_envs().count(), 0);
+        assert!(shell_command(" ", false).is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,664 @@
+//! Questions to ask the user
+//!
+//! Prompts are asked with [`Output::prompt`]. Human targets show the question
+//! and the answer is read from stdin (or the input set with
+//! [`Output::with_input`]). Invalid answers are rejected and the question is
+//! asked again.
+//!
+//! An output without human targets, e.g. one for `--json` mode, never waits
+//! for a human. The prompt is written to the JSON targets as
+//! `{"type":"prompt",...}` and the answer is read as a line like
+//! `{"answer":true}` instead.
+//!
+//! Answers that are known up front, e.g. from command line flags, can be
+//! given with the `answer` method of each prompt. Nothing is asked then.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate convey;
+//! use convey::prompt::{confirm, select};
+//! use std::io::Cursor;
+//!
+//! # fn main() -> Result<(), convey::Error> {
+//! # let test_target = convey::human::test();
+//! let out = convey::new()
+//!     .add_target(test_target.target())?
+//!     .with_input(Cursor::new("2\ny\n"))?;
+//!
+//! let color = out.prompt(select("Pick a color", vec!["red", "green"]))?;
+//! assert_eq!(color, 1);
+//!
+//! let sure = out.prompt(confirm("Paint it?").default(false))?;
+//! assert!(sure);
+//! # assert_eq!(test_target.to_string(), "\
+//! # ? Pick a color
+//! #   1) red
+//! #   2) green
+//! # > ? Paint it? [y/N] ");
+//! # Ok(()) }
+//! ```
+//!
+//! [`Output::prompt`]: ../struct.Output.html#method.prompt
+//! [`Output::with_input`]: ../struct.Output.html#method.with_input
+
+use crate::{human, json, Error};
+use serde_json::{json, Value};
+use std::io::BufRead;
+use std::sync::{Arc, Mutex};
+
+/// A question that can be asked with [`Output::prompt`]
+///
+/// [`Output::prompt`]: ../struct.Output.html#method.prompt
+pub trait Prompt {
+    /// What the question is answered with
+    type Answer;
+
+    /// The answer to use without asking, if it is already known
+    fn preset(&self) -> Option<Self::Answer>;
+
+    /// Show the question to a human
+    ///
+    /// This is written without a trailing newline, so the answer is typed
+    /// right after it.
+    fn render_question(&self, fmt: &mut human::Formatter) -> Result<(), Error>;
+
+    /// Parse a line typed by a human, `None` if it is not a valid answer
+    fn parse_answer(&self, line: &str) -> Option<Self::Answer>;
+
+    /// Describe the question for machines
+    ///
+    /// This is written as part of a `{"type":"prompt",...}` object.
+    fn to_json(&self) -> Value;
+
+    /// Parse the `answer` field of a JSON answer, `None` if it is not valid
+    fn parse_json(&self, answer: &Value) -> Option<Self::Answer>;
+}
+
+/// Ask a yes/no question
+///
+/// Humans answer with `y`, `yes`, `n`, or `no`. In JSON, the answer is a
+/// boolean.
+pub fn confirm<T: Into<String>>(message: T) -> Confirm {
+    Confirm {
+        message: message.into(),
+        default: None,
+        answer: None,
+    }
+}
+
+/// A yes/no question
+pub struct Confirm {
+    message: String,
+    default: Option<bool>,
+    answer: Option<bool>,
+}
+
+impl Confirm {
+    /// Answer used when a human just presses enter
+    pub fn default(mut self, answer: bool) -> Self {
+        self.default = Some(answer);
+        self
+    }
+
+    /// Don't ask if the answer is already known
+    pub fn answer(mut self, answer: Option<bool>) -> Self {
+        self.answer = answer;
+        self
+    }
+}
+
+impl Prompt for Confirm {
+    type Answer = bool;
+
+    fn preset(&self) -> Option<bool> {
+        self.answer
+    }
+
+    fn render_question(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        let choices = match self.default {
+            Some(true) => "[Y/n]",
+            Some(false) => "[y/N]",
+            None => "[y/n]",
+        };
+        write_question(fmt, &self.message)?;
+        fmt.write(" ")?;
+        fmt.write_styled("dim", choices)?;
+        fmt.write(" ")
+    }
+
+    fn parse_answer(&self, line: &str) -> Option<bool> {
+        match line.to_lowercase().as_str() {
+            "y" | "yes" => Some(true),
+            "n" | "no" => Some(false),
+            "" => self.default,
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ "kind": "confirm", "message": self.message, "default": self.default })
+    }
+
+    fn parse_json(&self, answer: &Value) -> Option<bool> {
+        answer.as_bool()
+    }
+}
+
+/// Ask for some text
+///
+/// Empty answers are only accepted if there is a default. In JSON, the
+/// answer is a string.
+pub fn input<T: Into<String>>(message: T) -> Input {
+    Input {
+        message: message.into(),
+        default: None,
+        answer: None,
+    }
+}
+
+/// A question that is answered with some text
+pub struct Input {
+    message: String,
+    default: Option<String>,
+    answer: Option<String>,
+}
+
+impl Input {
+    /// Answer used when a human just presses enter
+    pub fn default<T: Into<String>>(mut self, answer: T) -> Self {
+        self.default = Some(answer.into());
+        self
+    }
+
+    /// Don't ask if the answer is already known
+    pub fn answer(mut self, answer: Option<String>) -> Self {
+        self.answer = answer;
+        self
+    }
+}
+
+impl Prompt for Input {
+    type Answer = String;
+
+    fn preset(&self) -> Option<String> {
+        self.answer.clone()
+    }
+
+    fn render_question(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        write_question(fmt, &self.message)?;
+        if let Some(default) = &self.default {
+            fmt.write(" ")?;
+            fmt.write_styled("dim", format!("({})", default))?;
+        }
+        fmt.write(" ")
+    }
+
+    fn parse_answer(&self, line: &str) -> Option<String> {
+        if line.is_empty() {
+            self.default.clone()
+        } else {
+            Some(line.to_string())
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ "kind": "input", "message": self.message, "default": self.default })
+    }
+
+    fn parse_json(&self, answer: &Value) -> Option<String> {
+        answer.as_str().map(String::from)
+    }
+}
+
+/// Ask to pick one of some options
+///
+/// The answer is the index of the option. Humans answer with the number
+/// shown in front of an option (starting at 1) or its text. In JSON, the
+/// answer is the index (starting at 0) or the text.
+pub fn select<T: Into<String>>(message: T, options: Vec<T>) -> Select {
+    Select {
+        message: message.into(),
+        options: options.into_iter().map(Into::into).collect(),
+        default: None,
+        answer: None,
+    }
+}
+
+/// A question that is answered with one of some options
+pub struct Select {
+    message: String,
+    options: Vec<String>,
+    default: Option<usize>,
+    answer: Option<usize>,
+}
+
+impl Select {
+    /// Index of the option used when a human just presses enter
+    ///
+    /// Fails if there is no option with this index.
+    pub fn default(mut self, index: usize) -> Result<Self, Error> {
+        self.default = Some(check_index(&self.options, index)?);
+        Ok(self)
+    }
+
+    /// Don't ask if the answer is already known
+    ///
+    /// Fails if there is no option with this index.
+    pub fn answer(mut self, index: Option<usize>) -> Result<Self, Error> {
+        self.answer = index
+            .map(|index| check_index(&self.options, index))
+            .transpose()?;
+        Ok(self)
+    }
+}
+
+impl Prompt for Select {
+    type Answer = usize;
+
+    fn preset(&self) -> Option<usize> {
+        self.answer
+    }
+
+    fn render_question(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        write_question(fmt, &self.message)?;
+        write_options(fmt, &self.options)?;
+        fmt.write_styled("prompt", ">")?;
+        fmt.write(" ")?;
+        if let Some(default) = self.default {
+            fmt.write_styled("dim", format!("({})", default + 1))?;
+            fmt.write(" ")?;
+        }
+        Ok(())
+    }
+
+    fn parse_answer(&self, line: &str) -> Option<usize> {
+        if line.is_empty() {
+            return self.default;
+        }
+        find_option(&self.options, line)
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "select",
+            "message": self.message,
+            "options": self.options,
+            "default": self.default,
+        })
+    }
+
+    fn parse_json(&self, answer: &Value) -> Option<usize> {
+        option_from_json(&self.options, answer)
+    }
+}
+
+/// Ask to pick any number of some options
+///
+/// The answer is the list of indices of the picked options, in ascending
+/// order. Humans answer with the numbers shown in front of the options
+/// (starting at 1) or their texts, separated by commas. In JSON, the answer
+/// is an array of indices (starting at 0) or texts.
+pub fn multi_select<T: Into<String>>(message: T, options: Vec<T>) -> MultiSelect {
+    MultiSelect {
+        message: message.into(),
+        options: options.into_iter().map(Into::into).collect(),
+        default: Vec::new(),
+        answer: None,
+    }
+}
+
+/// A question that is answered with any number of some options
+pub struct MultiSelect {
+    message: String,
+    options: Vec<String>,
+    default: Vec<usize>,
+    answer: Option<Vec<usize>>,
+}
+
+impl MultiSelect {
+    /// Indices of the options used when a human just presses enter
+    ///
+    /// By default, nothing is picked then. Fails if there is no option with
+    /// one of the indices.
+    pub fn default(mut self, indices: Vec<usize>) -> Result<Self, Error> {
+        self.default = check_indices(&self.options, indices)?;
+        Ok(self)
+    }
+
+    /// Don't ask if the answer is already known
+    ///
+    /// Fails if there is no option with one of the indices.
+    pub fn answer(mut self, indices: Option<Vec<usize>>) -> Result<Self, Error> {
+        self.answer = indices
+            .map(|indices| check_indices(&self.options, indices))
+            .transpose()?;
+        Ok(self)
+    }
+}
+
+impl Prompt for MultiSelect {
+    type Answer = Vec<usize>;
+
+    fn preset(&self) -> Option<Vec<usize>> {
+        self.answer.clone()
+    }
+
+    fn render_question(&self, fmt: &mut human::Formatter) -> Result<(), Error> {
+        write_question(fmt, &self.message)?;
+        fmt.write(" ")?;
+        fmt.write_styled("dim", "(separate with commas)")?;
+        write_options(fmt, &self.options)?;
+        fmt.write_styled("prompt", ">")?;
+        fmt.write(" ")?;
+        if !self.default.is_empty() {
+            let default: Vec<String> = self.default.iter().map(|i| (i + 1).to_string()).collect();
+            fmt.write_styled("dim", format!("({})", default.join(",")))?;
+            fmt.write(" ")?;
+        }
+        Ok(())
+    }
+
+    fn parse_answer(&self, line: &str) -> Option<Vec<usize>> {
+        if line.is_empty() {
+            return Some(self.default.clone());
+        }
+        let picked = line
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| find_option(&self.options, part))
+            .collect::<Option<Vec<_>>>()?;
+        Some(sorted(picked))
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "multi_select",
+            "message": self.message,
+            "options": self.options,
+            "default": self.default,
+        })
+    }
+
+    fn parse_json(&self, answer: &Value) -> Option<Vec<usize>> {
+        let picked = answer
+            .as_array()?
+            .iter()
+            .map(|answer| option_from_json(&self.options, answer))
+            .collect::<Option<Vec<_>>>()?;
+        Some(sorted(picked))
+    }
+}
+
+fn write_question(fmt: &mut human::Formatter, message: &str) -> Result<(), Error> {
+    fmt.write_styled("prompt", "?")?;
+    fmt.write(" ")?;
+    fmt.write_styled("emphasis", message)
+}
+
+fn write_options(fmt: &mut human::Formatter, options: &[String]) -> Result<(), Error> {
+    fmt.write("\n")?;
+    for (index, option) in options.iter().enumerate() {
+        fmt.write(format!("  {}) {}\n", index + 1, option))?;
+    }
+    Ok(())
+}
+
+/// Find an option by its number (starting at 1) or text
+fn find_option(options: &[String], answer: &str) -> Option<usize> {
+    match answer.parse::<usize>() {
+        Ok(number) if (1..=options.len()).contains(&number) => Some(number - 1),
+        _ => options.iter().position(|option| option == answer),
+    }
+}
+
+/// Find an option by its index or text
+fn option_from_json(options: &[String], answer: &Value) -> Option<usize> {
+    match answer {
+        Value::Number(index) => index
+            .as_u64()
+            .map(|index| index as usize)
+            .filter(|&index| index < options.len()),
+        Value::String(text) => options.iter().position(|option| option == text),
+        _ => None,
+    }
+}
+
+/// Make sure there is an option with `index`
+fn check_index(options: &[String], index: usize) -> Result<usize, Error> {
+    if index < options.len() {
+        Ok(index)
+    } else {
+        Err(Error::prompt_error(format!(
+            "no option with index {}, there are {} options",
+            index,
+            options.len()
+        )))
+    }
+}
+
+fn check_indices(options: &[String], indices: Vec<usize>) -> Result<Vec<usize>, Error> {
+    let indices = indices
+        .into_iter()
+        .map(|index| check_index(options, index))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(sorted(indices))
+}
+
+fn sorted(mut indices: Vec<usize>) -> Vec<usize> {
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+/// Where answers are read from
+pub(crate) type Reader = Arc<Mutex<Box<dyn BufRead + Send>>>;
+
+/// Ask humans if there are any, machines otherwise
+pub(crate) fn ask<P: Prompt>(
+    prompt: &P,
+    humans: &[Arc<Mutex<human::Formatter>>],
+    machines: &[Arc<Mutex<json::Formatter>>],
+    input: &mut dyn BufRead,
+) -> Result<P::Answer, Error> {
+    if let Some(answer) = prompt.preset() {
+        return Ok(answer);
+    }
+
+    if humans.is_empty() {
+        return ask_machines(prompt, machines, input);
+    }
+
+    loop {
+        for fmt in humans {
+            let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+            prompt.render_question(&mut fmt)?;
+            fmt.flush()?;
+        }
+
+        let line = read_line(input)?;
+        if let Some(answer) = prompt.parse_answer(line.as_deref().unwrap_or("").trim()) {
+            return Ok(answer);
+        }
+        if line.is_none() {
+            return Err(Error::prompt_error("input ended without an answer".into()));
+        }
+
+        for fmt in humans {
+            let fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+            fmt.write_styled("error", "Invalid answer, please try again")?;
+            fmt.write("\n")?;
+        }
+    }
+}
+
+fn ask_machines<P: Prompt>(
+    prompt: &P,
+    machines: &[Arc<Mutex<json::Formatter>>],
+    input: &mut dyn BufRead,
+) -> Result<P::Answer, Error> {
+    let mut question = prompt.to_json();
+    if let Value::Object(fields) = &mut question {
+        fields.insert("type".into(), "prompt".into());
+    }
+    for fmt in machines {
+        let mut fmt = fmt.lock().map_err(|e| Error::sync_error(&e))?;
+        fmt.write(&question)?;
+        fmt.write_separator()?;
+        fmt.flush()?;
+    }
+
+    let line = read_line(input)?
+        .ok_or_else(|| Error::prompt_error("input ended without an answer".into()))?;
+    let value: Value = serde_json::from_str(&line)?;
+    value
+        .get("answer")
+        .and_then(|answer| prompt.parse_json(answer))
+        .ok_or_else(|| Error::prompt_error(format!("invalid answer `{}`", line.trim())))
+}
+
+/// Read a line without its line break, `None` at the end of the input
+fn read_line(input: &mut dyn BufRead) -> Result<Option<String>, Error> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{confirm, input, multi_select, select};
+    use crate::{human, json, Error};
+    use crossbeam_channel as channel;
+    use std::io::{self, BufReader, Cursor, Read};
+    use std::thread;
+
+    /// Input that blocks until a line is sent
+    struct Lines(channel::Receiver<String>);
+
+    impl Read for Lines {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let line = self.0.recv().unwrap_or_default();
+            buf[..line.len()].copy_from_slice(line.as_bytes());
+            Ok(line.len())
+        }
+    }
+
+    #[test]
+    fn prints_while_waiting_for_answers() -> Result<(), Error> {
+        let test_target = human::test();
+        let (sender, receiver) = channel::unbounded();
+        let out = crate::new()
+            .add_target(test_target.target())?
+            .with_input(BufReader::new(Lines(receiver)))?;
+
+        let asking = out.clone();
+        let prompt = thread::spawn(move || asking.prompt(input("Name?")));
+        while !test_target.to_string().contains("Name?") {
+            thread::yield_now();
+        }
+        out.print("still printing")?;
+        sender.send("convey\n".to_string()).unwrap();
+
+        assert_eq!(prompt.join().unwrap()?, "convey");
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_indices_without_options() {
+        assert!(select("Which?", vec!["a", "b"]).default(2).is_err());
+        assert!(select("Which?", vec!["a", "b"]).answer(Some(1)).is_ok());
+        assert!(multi_select("Which?", vec!["a"])
+            .default(vec![0, 1])
+            .is_err());
+        assert!(multi_select("Which?", vec!["a"])
+            .answer(Some(vec![3]))
+            .is_err());
+    }
+
+    #[test]
+    fn asks_again_after_invalid_answers() -> Result<(), Error> {
+        let test_target = human::test();
+        let out = crate::new()
+            .add_target(test_target.target())?
+            .with_input(Cursor::new("maybe\n\n"))?;
+
+        assert!(out.prompt(confirm("Sure?").default(true))?);
+        assert_eq!(
+            test_target.to_string(),
+            "? Sure? [Y/n] Invalid answer, please try again\n? Sure? [Y/n] "
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fails_at_end_of_input() -> Result<(), Error> {
+        let out = crate::new()
+            .add_target(human::test().target())?
+            .with_input(Cursor::new(""))?;
+
+        assert!(out.prompt(input("Name?")).is_err());
+        assert_eq!(out.prompt(input("Name?").default("anon"))?, "anon");
+        Ok(())
+    }
+
+    #[test]
+    fn picks_options_by_number_or_text() -> Result<(), Error> {
+        let test_target = human::test();
+        let out = crate::new()
+            .add_target(test_target.target())?
+            .with_input(Cursor::new("3, a,1\n"))?;
+
+        let options = vec!["a", "b", "c"];
+        let answer = out.prompt(multi_select("Which?", options).default(vec![1])?)?;
+        assert_eq!(answer, vec![0, 2]);
+        assert_eq!(
+            test_target.to_string(),
+            "? Which? (separate with commas)\n  1) a\n  2) b\n  3) c\n> (2) "
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reads_json_answers() -> Result<(), Error> {
+        let test_target = json::test();
+        let out = crate::new()
+            .add_target(test_target.target())?
+            .with_input(Cursor::new("{\"answer\":\"b\"}\n{\"answer\":[2,0]}\n"))?;
+
+        assert_eq!(out.prompt(select("Which?", vec!["a", "b"]))?, 1);
+        let options = vec!["a", "b", "c"];
+        assert_eq!(out.prompt(multi_select("Which?", options))?, vec![0, 2]);
+        assert_eq!(
+            test_target.to_string(),
+            "{\"default\":null,\"kind\":\"select\",\"message\":\"Which?\",\"options\":[\"a\",\"b\"],\"type\":\"prompt\"}\n\
+             {\"default\":[],\"kind\":\"multi_select\",\"message\":\"Which?\",\"options\":[\"a\",\"b\",\"c\"],\"type\":\"prompt\"}\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_json_answers() -> Result<(), Error> {
+        let out = crate::new()
+            .add_target(json::test().target())?
+            .with_input(Cursor::new("{\"answer\":\"yes\"}\n"))?;
+
+        assert!(out.prompt(confirm("Sure?")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn uses_preset_answers() -> Result<(), Error> {
+        let test_target = json::test();
+        let out = crate::new()
+            .add_target(test_target.target())?
+            .with_input(Cursor::new(""))?;
+
+        assert!(!out.prompt(confirm("Sure?").answer(Some(false)))?);
+        out.flush()?;
+        assert_eq!(test_target.to_string(), "");
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
+use crate::{Error, Target};
 use std::io;
 use std::sync::{Arc, RwLock};
-use termcolor::{Buffer, ColorSpec, WriteColor};
+use termcolor::{Buffer, ColorSpec, HyperlinkSpec, WriteColor};
 
 #[derive(Clone)]
 pub(crate) struct TestBuffer(pub(crate) Arc<RwLock<Buffer>>);
@@ -32,15 +33,63 @@ impl WriteColor for TestBuffer {
         buffer.supports_color()
     }
 
+    fn supports_hyperlinks(&self) -> bool {
+        let target = self.0.clone();
+        let buffer = target.read().unwrap();
+        buffer.supports_hyperlinks()
+    }
+
     fn set_color(&mut self, spec: &ColorSpec) -> Result<(), io::Error> {
         let target = self.0.clone();
         let mut buffer = target.write().unwrap();
         buffer.set_color(spec)
     }
 
+    fn set_hyperlink(&mut self, link: &HyperlinkSpec) -> Result<(), io::Error> {
+        let target = self.0.clone();
+        let mut buffer = target.write().unwrap();
+        buffer.set_hyperlink(link)
+    }
+
     fn reset(&mut self) -> Result<(), io::Error> {
         let target = self.0.clone();
         let mut buffer = target.write().unwrap();
         buffer.reset()
     }
 }
+
+/// A test output target that writes to a buffer, see e.g. `json::test`
+pub struct TestTarget<F> {
+    buffer: TestBuffer,
+    formatter: fn(TestBuffer) -> Result<F, Error>,
+    target: fn(F) -> Target,
+}
+
+impl<F> TestTarget<F> {
+    pub(crate) fn new(
+        formatter: fn(TestBuffer) -> Result<F, Error>,
+        target: fn(F) -> Target,
+    ) -> Self {
+        TestTarget {
+            buffer: Buffer::no_color().into(),
+            formatter,
+            target,
+        }
+    }
+
+    pub fn formatter(&self) -> F {
+        (self.formatter)(self.buffer.clone()).unwrap()
+    }
+
+    pub fn target(&self) -> Target {
+        (self.target)(self.formatter())
+    }
+}
+
+impl<F> ::std::fmt::Display for TestTarget<F> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> Result<(), ::std::fmt::Error> {
+        let target = self.buffer.0.clone();
+        let buffer = target.read().unwrap();
+        write!(f, "{}", String::from_utf8_lossy(buffer.as_slice()))
+    }
+}
@@ -0,0 +1,533 @@
+//! Named styles for human output
+//!
+//! Instead of hard-coding colors everywhere, components refer to semantic
+//! style names like `"error"` or `"path"`. A [`Theme`] maps these names to
+//! concrete [`Style`]s and is registered on human targets using
+//! [`Target::with_theme`](../struct.Target.html#method.with_theme).
+//!
+//! # Style names
+//!
+//! The built-in themes define the following styles:
+//!
+//! - `error`, `warning`, `success`, `info`
+//! - `emphasis`, `dim`, `code`, `path`
+//! - `note`, `help`, `gutter` (used by diagnostics)
+//! - `heading`, `rule` (used by headings and sections)
+//! - `diff.insert`, `diff.delete`, `diff.insert.word`, `diff.delete.word`,
+//!   `diff.hunk` (used by diffs)
+//! - `prompt` (used by prompts)
+//! - `level.error`, `level.warn`, `level.info`, `level.debug`, `level.trace`
+//!   (used by the logger)
+//!
+//! You are free to add your own names, of course.
+//!
+//! Besides styles, a theme names the `syntect` theme used to highlight
+//! [`code`](../components/fn.code.html), see [`Theme::with_syntax_theme`].
+//!
+//! # Theme files
+//!
+//! Themes can be loaded from small config files, which contain one style per
+//! line. The syntax for a style is the same as git's color config: a list of
+//! attributes (`bold`, `dim`, `italic`, `underline`, `strike`, `reverse`,
+//! `intense`) and up to two colors, the first
+//! being the foreground and the second the background color. Use `normal` to
+//! skip the foreground color. Colors use the syntax described in the [`color`]
+//! module. Lines starting with `#` are comments. The `code.theme` line isn't a
+//! style but the name of the syntax highlighting theme.
+//!
+//! ```text
+//! # my-theme.conf
+//! code.theme = InspiredGitHub
+//! error = bold red
+//! path = underline
+//! code = normal black
+//! level.info = 33
+//! emphasis = bold #ff8800
+//! ```
+//!
+//! [`color`]: ../color/index.html
+
+use crate::color::{parse_color, Color};
+use crate::Error;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use termcolor::ColorSpec;
+
+/// Styling information for a piece of text
+///
+/// Attributes that are not set are inherited when combined with another
+/// style, see [`Style::merge`].
+///
+/// Not all terminals support all attributes. Unsupported ones are ignored.
+/// `reverse` can't be written as an attribute, so it is emulated by swapping
+/// the foreground and background colors. A missing foreground color becomes
+/// white and a missing background color black, which doesn't stand out on a
+/// light terminal, so set both colors when using `reverse` there.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: Option<bool>,
+    dim: Option<bool>,
+    italic: Option<bool>,
+    underline: Option<bool>,
+    strikethrough: Option<bool>,
+    reverse: Option<bool>,
+    intense: Option<bool>,
+}
+
+impl Style {
+    /// Create a new style without any attributes
+    pub fn new() -> Self {
+        Style::default()
+    }
+
+    /// Set foreground color
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Set background color
+    pub fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    /// Set bold
+    pub fn bold(mut self, yes: bool) -> Self {
+        self.bold = Some(yes);
+        self
+    }
+
+    /// Set dim, for secondary information
+    pub fn dim(mut self, yes: bool) -> Self {
+        self.dim = Some(yes);
+        self
+    }
+
+    /// Set italic
+    pub fn italic(mut self, yes: bool) -> Self {
+        self.italic = Some(yes);
+        self
+    }
+
+    /// Set underline
+    pub fn underline(mut self, yes: bool) -> Self {
+        self.underline = Some(yes);
+        self
+    }
+
+    /// Set strikethrough
+    pub fn strikethrough(mut self, yes: bool) -> Self {
+        self.strikethrough = Some(yes);
+        self
+    }
+
+    /// Set reverse video, i.e., swap foreground and background colors
+    ///
+    /// This is emulated with colors, see [`Style`] for what that means if no
+    /// colors are set.
+    pub fn reverse(mut self, yes: bool) -> Self {
+        self.reverse = Some(yes);
+        self
+    }
+
+    /// Set intense
+    pub fn intense(mut self, yes: bool) -> Self {
+        self.intense = Some(yes);
+        self
+    }
+
+    /// Combine two styles, with attributes set in `other` taking precedence
+    pub fn merge(&self, other: &Style) -> Style {
+        Style {
+            fg: other.fg.or(self.fg),
+            bg: other.bg.or(self.bg),
+            bold: other.bold.or(self.bold),
+            dim: other.dim.or(self.dim),
+            italic: other.italic.or(self.italic),
+            underline: other.underline.or(self.underline),
+            strikethrough: other.strikethrough.or(self.strikethrough),
+            reverse: other.reverse.or(self.reverse),
+            intense: other.intense.or(self.intense),
+        }
+    }
+
+    /// Convert to a termcolor color specification
+    pub fn to_color_spec(&self) -> ColorSpec {
+        let (fg, bg) = if self.reverse.unwrap_or(false) {
+            (
+                Some(self.bg.unwrap_or(Color::Black)),
+                Some(self.fg.unwrap_or(Color::White)),
+            )
+        } else {
+            (self.fg, self.bg)
+        };
+
+        let mut spec = ColorSpec::new();
+        spec.set_fg(fg)
+            .set_bg(bg)
+            .set_bold(self.bold.unwrap_or(false))
+            .set_dimmed(self.dim.unwrap_or(false))
+            .set_italic(self.italic.unwrap_or(false))
+            .set_underline(self.underline.unwrap_or(false))
+            .set_strikethrough(self.strikethrough.unwrap_or(false))
+            .set_intense(self.intense.unwrap_or(false));
+        spec
+    }
+}
+
+/// Parse a style from a git-like color specification
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// use convey::{color::Color, theme::Style};
+///
+/// # fn main() -> Result<(), convey::Error> {
+/// let style: Style = "bold red white".parse()?;
+/// assert_eq!(style, Style::new().bold(true).fg(Color::Red).bg(Color::White));
+/// # Ok(()) }
+/// ```
+impl FromStr for Style {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut style = Style::new();
+        let mut colors = 0;
+
+        for token in s.split_whitespace() {
+            match token {
+                "bold" => style.bold = Some(true),
+                "dim" => style.dim = Some(true),
+                "italic" => style.italic = Some(true),
+                "underline" | "ul" => style.underline = Some(true),
+                "strike" | "strikethrough" => style.strikethrough = Some(true),
+                "reverse" => style.reverse = Some(true),
+                "intense" | "bright" => style.intense = Some(true),
+                "normal" if colors == 0 => colors += 1,
+                color => {
+                    let color = Some(parse_color(color)?);
+                    match colors {
+                        0 => style.fg = color,
+                        1 => style.bg = color,
+                        _ => {
+                            return Err(Error::theme_error(format!(
+                                "too many colors in style `{}`",
+                                s
+                            )));
+                        }
+                    }
+                    colors += 1;
+                }
+            }
+        }
+
+        Ok(style)
+    }
+}
+
+/// A set of named styles
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    styles: BTreeMap<String, Style>,
+    syntax: Option<String>,
+}
+
+/// The default theme is [`Theme::dark`]
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    /// Create a new theme that contains no styles at all
+    pub fn empty() -> Self {
+        Theme {
+            styles: BTreeMap::new(),
+            syntax: None,
+        }
+    }
+
+    /// Built-in theme for terminals with a dark background
+    ///
+    /// This is the default theme of human targets.
+    pub fn dark() -> Self {
+        Theme::empty()
+            .with_syntax_theme("base16-ocean.dark")
+            .with("error", Style::new().fg(Color::Red).bold(true))
+            .with("warning", Style::new().fg(Color::Yellow).bold(true))
+            .with("success", Style::new().fg(Color::Green).bold(true))
+            .with("info", Style::new().fg(Color::Cyan))
+            .with("emphasis", Style::new().bold(true))
+            .with("dim", Style::new().fg(Color::Black).intense(true))
+            .with("code", Style::new().fg(Color::Yellow))
+            .with("path", Style::new().fg(Color::Cyan).underline(true))
+            .with("note", Style::new().fg(Color::Green).bold(true))
+            .with("help", Style::new().fg(Color::Cyan).bold(true))
+            .with(
+                "gutter",
+                Style::new().fg(Color::Blue).intense(true).bold(true),
+            )
+            .with("heading", Style::new().bold(true))
+            .with("rule", Style::new().fg(Color::Black).intense(true))
+            .with("diff.insert", Style::new().fg(Color::Green))
+            .with("diff.delete", Style::new().fg(Color::Red))
+            .with(
+                "diff.insert.word",
+                Style::new().fg(Color::Green).reverse(true),
+            )
+            .with(
+                "diff.delete.word",
+                Style::new().fg(Color::Red).reverse(true),
+            )
+            .with("diff.hunk", Style::new().fg(Color::Cyan))
+            .with("prompt", Style::new().fg(Color::Green).bold(true))
+            .with("level.error", Style::new().fg(Color::Red))
+            .with("level.warn", Style::new().fg(Color::Yellow))
+            .with("level.info", Style::new().fg(Color::Blue))
+            .with("level.debug", Style::new().fg(Color::Cyan))
+            .with("level.trace", Style::new().fg(Color::White))
+    }
+
+    /// Built-in theme for terminals with a light background
+    pub fn light() -> Self {
+        Theme::dark()
+            .with_syntax_theme("InspiredGitHub")
+            .with("dim", Style::new().fg(Color::White))
+            .with("code", Style::new().fg(Color::Magenta))
+            .with("path", Style::new().fg(Color::Blue).underline(true))
+            .with("rule", Style::new().fg(Color::White))
+            .with("level.trace", Style::new().fg(Color::Black))
+    }
+
+    /// Built-in theme that doesn't use any colors
+    ///
+    /// Code isn't syntax highlighted with this theme.
+    pub fn monochrome() -> Self {
+        Theme::empty()
+            .with("error", Style::new().bold(true))
+            .with("warning", Style::new().bold(true))
+            .with("success", Style::new())
+            .with("info", Style::new())
+            .with("emphasis", Style::new().bold(true))
+            .with("dim", Style::new().dim(true))
+            .with("code", Style::new())
+            .with("path", Style::new().underline(true))
+            .with("note", Style::new().bold(true))
+            .with("help", Style::new().bold(true))
+            .with("gutter", Style::new())
+            .with("heading", Style::new().bold(true))
+            .with("rule", Style::new().dim(true))
+            .with("diff.insert", Style::new())
+            .with("diff.delete", Style::new())
+            .with("diff.insert.word", Style::new().underline(true))
+            .with("diff.delete.word", Style::new().underline(true))
+            .with("diff.hunk", Style::new().dim(true))
+            .with("prompt", Style::new().bold(true))
+            .with("level.error", Style::new().bold(true))
+            .with("level.warn", Style::new().bold(true))
+            .with("level.info", Style::new())
+            .with("level.debug", Style::new())
+            .with("level.trace", Style::new())
+    }
+
+    /// Load a theme from a config file
+    ///
+    /// See the [module level documentation](index.html#theme-files) for the
+    /// format.
+    pub fn from_file<T: AsRef<Path>>(path: T) -> Result<Self, Error> {
+        let content = fs::read_to_string(path)?;
+        content.parse()
+    }
+
+    /// Add a style, replacing any existing style of the same name
+    pub fn with<N: Into<String>>(mut self, name: N, style: Style) -> Self {
+        self.styles.insert(name.into(), style);
+        self
+    }
+
+    /// Highlight code with a theme that comes with `syntect`
+    ///
+    /// The built-in themes use `base16-ocean.dark` and `InspiredGitHub`,
+    /// the monochrome and empty ones don't highlight code at all.
+    pub fn with_syntax_theme<N: Into<String>>(mut self, name: N) -> Self {
+        self.syntax = Some(name.into());
+        self
+    }
+
+    /// The name of the syntax highlighting theme, if code is highlighted
+    pub fn syntax_theme(&self) -> Option<&str> {
+        self.syntax.as_deref()
+    }
+
+    /// Add all styles of another theme, replacing existing ones
+    pub fn merge(mut self, other: Theme) -> Self {
+        self.styles.extend(other.styles);
+        if other.syntax.is_some() {
+            self.syntax = other.syntax;
+        }
+        self
+    }
+
+    /// Look up a style by name
+    pub fn get(&self, name: &str) -> Option<&Style> {
+        self.styles.get(name)
+    }
+
+    /// Iterate over all styles in this theme, ordered by name
+    pub fn styles(&self) -> impl Iterator<Item = (&str, &Style)> {
+        self.styles
+            .iter()
+            .map(|(name, style)| (name.as_str(), style))
+    }
+}
+
+/// Parse a theme from the content of a theme file
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate convey;
+/// use convey::theme::{Style, Theme};
+///
+/// # fn main() -> Result<(), convey::Error> {
+/// let theme: Theme = "# my theme\nerror = bold red\npath = underline".parse()?;
+/// assert_eq!(theme.get("path"), Some(&Style::new().underline(true)));
+/// # Ok(()) }
+/// ```
+impl FromStr for Theme {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut theme = Theme::empty();
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let name = parts.next().unwrap_or_default().trim();
+            let style = match parts.next() {
+                Some(style) if !name.is_empty() => style,
+                _ => {
+                    return Err(Error::theme_error(format!(
+                        "line {}: expected `name = style`",
+                        i + 1
+                    )));
+                }
+            };
+
+            if name == "code.theme" {
+                theme.syntax = Some(style.trim().to_string());
+                continue;
+            }
+            theme.styles.insert(name.to_string(), style.parse()?);
+        }
+
+        Ok(theme)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Style, Theme};
+    use crate::color::Color;
+
+    #[test]
+    fn parses_git_like_styles() {
+        let style: Style = "bold 208 0,0,255".parse().unwrap();
+        assert_eq!(
+            style,
+            Style::new()
+                .bold(true)
+                .fg(Color::Ansi256(208))
+                .bg(Color::Rgb(0, 0, 255))
+        );
+
+        let style: Style = "#ff8800".parse().unwrap();
+        assert_eq!(style, Style::new().fg(Color::Rgb(255, 136, 0)));
+
+        let style: Style = "dim italic strike reverse".parse().unwrap();
+        assert_eq!(
+            style,
+            Style::new()
+                .dim(true)
+                .italic(true)
+                .strikethrough(true)
+                .reverse(true)
+        );
+
+        let style: Style = "normal yellow".parse().unwrap();
+        assert_eq!(style, Style::new().bg(Color::Yellow));
+
+        assert!("red green blue".parse::<Style>().is_err());
+        assert!("shiny".parse::<Style>().is_err());
+    }
+
+    #[test]
+    fn emulates_reverse_by_swapping_colors() {
+        let spec = Style::new().fg(Color::Red).reverse(true).to_color_spec();
+        assert_eq!(spec.fg(), Some(&Color::Black));
+        assert_eq!(spec.bg(), Some(&Color::Red));
+
+        let spec = Style::new().reverse(true).to_color_spec();
+        assert_eq!(spec.fg(), Some(&Color::Black));
+        assert_eq!(spec.bg(), Some(&Color::White));
+    }
+
+    #[test]
+    fn merges_styles() {
+        let base = Style::new().fg(Color::Red).bold(true);
+        let over = Style::new().fg(Color::Blue).underline(true);
+
+        assert_eq!(
+            base.merge(&over),
+            Style::new().fg(Color::Blue).bold(true).underline(true)
+        );
+    }
+
+    #[test]
+    fn parses_theme_files() {
+        let theme: Theme = "
+            # comment
+            error = bold red
+
+            level.info = blue
+        "
+        .parse()
+        .unwrap();
+
+        assert_eq!(theme.styles().count(), 2);
+        assert_eq!(
+            theme.get("error"),
+            Some(&Style::new().bold(true).fg(Color::Red))
+        );
+        assert_eq!(theme.get("level.info"), Some(&Style::new().fg(Color::Blue)));
+
+        assert!("error: red".parse::<Theme>().is_err());
+
+        let theme: Theme = "code.theme = Solarized (light)".parse().unwrap();
+        assert_eq!(theme.styles().count(), 0);
+        assert_eq!(theme.syntax_theme(), Some("Solarized (light)"));
+        assert_eq!(
+            Theme::light().merge(theme).syntax_theme(),
+            Some("Solarized (light)")
+        );
+    }
+
+    #[test]
+    fn merges_themes() {
+        let theme = Theme::dark().merge("error = underline".parse().unwrap());
+
+        assert_eq!(theme.get("error"), Some(&Style::new().underline(true)));
+        assert_eq!(theme.get("warning"), Theme::dark().get("warning"));
+    }
+}
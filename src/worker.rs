@@ -0,0 +1,146 @@
+//! Writing output on a background thread
+//!
+//! The JSON, HTML, and Markdown formatters only ever write bytes, so they
+//! share this worker. Printing an item just sends its output here and doesn't
+//! wait for the writer.
+
+use crate::Error;
+use crossbeam_channel as channel;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A thread that owns a writer and writes everything sent to it
+pub(crate) struct Worker {
+    sender: channel::Sender<Message>,
+    receiver: channel::Receiver<Response>,
+    // Only an option so we can `take` this in `Drop::drop`
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl Worker {
+    /// Start the thread, creating the writer on it with `init`
+    pub(crate) fn init_with<W: Write, F: FnOnce() -> Result<W, Error> + Send + 'static>(
+        init: F,
+    ) -> Result<Self, Error> {
+        let (message_sender, message_receiver) = channel::unbounded();
+        let (response_sender, response_receiver) = channel::bounded(0);
+
+        let worker = thread::spawn(move || {
+            let mut buffer = match init() {
+                Ok(buf) => {
+                    let _ = response_sender.send(Response::StartedSuccessfully);
+                    buf
+                }
+                Err(e) => {
+                    let _ = response_sender.send(Response::Error(e));
+                    return;
+                }
+            };
+
+            macro_rules! maybe_log_error {
+                () => {
+                    |e| {
+                        if cfg!(debug_assertions) {
+                            eprintln!("{}", e)
+                        }
+                    }
+                };
+            }
+
+            loop {
+                match message_receiver.recv() {
+                    Ok(Message::Write(data)) => {
+                        let _ = buffer.write_all(&data).map_err(maybe_log_error!());
+                    }
+                    Ok(Message::Flush) => {
+                        let _ = buffer.flush().map_err(maybe_log_error!());
+                        let _ = response_sender.send(Response::Flushed);
+                    }
+                    Ok(Message::Exit) | Err(_) => {
+                        let _ = buffer.flush().map_err(maybe_log_error!());
+                        break;
+                    }
+                };
+            }
+        });
+
+        match response_receiver.recv() {
+            Ok(Response::Error(error)) => Err(error),
+            Ok(Response::StartedSuccessfully) => Ok(Worker {
+                worker: Some(worker),
+                sender: message_sender,
+                receiver: response_receiver,
+            }),
+            msg => Err(Error::worker_error(format!("unexpected message {:?}", msg))),
+        }
+    }
+
+    pub(crate) fn write(&self, data: Vec<u8>) -> Result<(), Error> {
+        self.sender.send(Message::Write(data))?;
+        Ok(())
+    }
+
+    /// Wait until everything sent so far is written
+    pub(crate) fn flush(&self) -> Result<(), Error> {
+        self.sender.send(Message::Flush)?;
+
+        match self.receiver.recv() {
+            Ok(Response::Flushed) => Ok(()),
+            msg => Err(Error::worker_error(format!("unexpected message {:?}", msg))),
+        }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        let _ = self.sender.send(Message::Exit);
+        // TODO: Docs say this may panic, so have a look at how to deal with that.
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Message {
+    Write(Vec<u8>),
+    Flush,
+    Exit,
+}
+
+#[derive(Debug)]
+enum Response {
+    StartedSuccessfully,
+    Error(Error),
+    Flushed,
+}
+
+/// Where the output of the HTML and Markdown formatters goes
+pub(crate) enum Backend {
+    Worker(Arc<Worker>),
+    /// Collects output instead of sending it to the worker
+    Capture(Arc<Mutex<String>>),
+}
+
+impl Backend {
+    pub(crate) fn write(&self, text: String) -> Result<(), Error> {
+        match self {
+            Backend::Worker(worker) => worker.write(text.into_bytes()),
+            Backend::Capture(captured) => {
+                captured
+                    .lock()
+                    .map_err(|e| Error::sync_error(&e))?
+                    .push_str(&text);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn flush(&self) -> Result<(), Error> {
+        match self {
+            Backend::Worker(worker) => worker.flush(),
+            Backend::Capture(_) => Ok(()),
+        }
+    }
+}